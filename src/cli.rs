@@ -0,0 +1,227 @@
+use crate::scene::{
+    config::{
+        Backend, GameConfig, DEFAULT_BOARD_HEIGHT, DEFAULT_BOARD_WIDTH, DEFAULT_CUBE_SPACING,
+        DEFAULT_DENSITY, DEFAULT_TICK,
+    },
+    gradient::AgeGradient,
+    rule::Rule,
+    session::{load_session, SessionFile},
+    spacetime::DEFAULT_SPACETIME_DEPTH,
+};
+use clap::Parser;
+use std::{path::PathBuf, time::Duration};
+
+/// Runs Conway's Game of Life in 3d
+#[derive(Parser, Debug)]
+#[command(version, about)]
+pub struct Cli {
+    /// A session file to resume, which is also where F5 saves
+    pub session: Option<PathBuf>,
+
+    /// Simulation to run: bounded, or sparse or hashlife for an unbounded
+    /// universe. Defaults to hashlife for Macrocell patterns and bounded
+    /// otherwise
+    #[arg(long)]
+    pub backend: Option<Backend>,
+
+    /// Columns of the board, of which the middle half is in view
+    #[arg(long, default_value_t = DEFAULT_BOARD_WIDTH)]
+    pub width: usize,
+
+    /// Rows of the board, of which the middle half is in view
+    #[arg(long, default_value_t = DEFAULT_BOARD_HEIGHT)]
+    pub height: usize,
+
+    /// Rule in B/S notation, like B36/S23. Defaults to the pattern's rule,
+    /// or B3/S23 without one
+    #[arg(long)]
+    pub rule: Option<Rule>,
+
+    /// Seed of the starting soup, which is random without one
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Chance each cell of the starting soup is alive
+    #[arg(long, default_value_t = DEFAULT_DENSITY)]
+    pub density: f64,
+
+    /// A pattern file to start from instead of a soup
+    #[arg(long)]
+    pub pattern: Option<PathBuf>,
+
+    /// Milliseconds between ticks
+    #[arg(long, default_value_t = DEFAULT_TICK.as_millis() as u64)]
+    pub tick_ms: u64,
+
+    /// Start running instead of paused
+    #[arg(long)]
+    pub running: bool,
+
+    /// Colors of live cells by age, as comma separated stops of age and
+    /// hex color, like 0:fff299,32:4073f2
+    #[arg(long)]
+    pub gradient: Option<AgeGradient>,
+
+    /// Past generations the spacetime view stacks below the board
+    #[arg(long, default_value_t = DEFAULT_SPACETIME_DEPTH)]
+    pub spacetime_depth: usize,
+
+    /// Distance between neighboring cubes, which are 2 wide
+    #[arg(long, default_value_t = DEFAULT_CUBE_SPACING)]
+    pub spacing: f32,
+
+    /// Width of the window in logical pixels
+    #[arg(long, default_value_t = 1280.)]
+    pub window_width: f32,
+
+    /// Height of the window in logical pixels
+    #[arg(long, default_value_t = 720.)]
+    pub window_height: f32,
+}
+
+impl Cli {
+    /// The settings the game starts from. A session being resumed sets the
+    /// board's size to the size it was saved at, while one that can't be
+    /// read leaves it alone, to be reported when it's resumed.
+    /// Errors if any of them are out of range.
+    pub fn config(&self) -> anyhow::Result<GameConfig> {
+        let (width, height) = match self.session.as_ref().map(load_session) {
+            Some(Ok(session)) => (session.cells.width(), session.cells.height()),
+            _ => (self.width, self.height),
+        };
+        let mut config = GameConfig::new(width, height)?
+            .with_density(self.density)?
+            .with_tick(Duration::from_millis(self.tick_ms))?
+            .with_paused(!self.running)
+            .with_spacetime_depth(self.spacetime_depth)?
+            .with_spacing(self.spacing)?;
+        if let Some(backend) = self.backend {
+            config = config.with_backend(backend);
+        }
+        if let Some(rule) = self.rule {
+            config = config.with_rule(rule);
+        }
+        if let Some(seed) = self.seed {
+            config = config.with_seed(seed);
+        }
+        if let Some(pattern) = &self.pattern {
+            config = config.with_pattern(pattern);
+        }
+        if let Some(gradient) = &self.gradient {
+            config = config.with_gradient(gradient.clone());
+        }
+        Ok(config)
+    }
+
+    /// The file sessions are saved to, which is resumed at startup if it
+    /// was passed.
+    pub fn session_file(&self) -> SessionFile {
+        match &self.session {
+            Some(path) => SessionFile::new(path).with_resume(true),
+            None => SessionFile::default(),
+        }
+    }
+
+    #[inline]
+    pub fn window_size(&self) -> (f32, f32) {
+        (self.window_width, self.window_height)
+    }
+}
+
+#[cfg(test)]
+mod cli_tests {
+    use super::Cli;
+    use crate::scene::{
+        bitboard::BitBoard,
+        config::{Backend, GameConfig},
+        session::{save_session, Session},
+    };
+    use bevy::prelude::*;
+    use clap::Parser;
+    use std::{env, fs, time::Duration};
+
+    #[test]
+    fn defaults_match_the_config() -> anyhow::Result<()> {
+        let cli = Cli::try_parse_from(["conway"])?;
+        assert_eq!(cli.config()?, GameConfig::default());
+        assert!(!cli.session_file().path().as_os_str().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn parses_every_option() -> anyhow::Result<()> {
+        let cli = Cli::try_parse_from([
+            "conway",
+            "saved.session",
+            "--backend=sparse",
+            "--width=64",
+            "--height=32",
+            "--rule=B36/S23",
+            "--seed=7",
+            "--density=0.25",
+            "--pattern=glider.rle",
+            "--tick-ms=100",
+            "--running",
+            "--gradient=0:ffffff,4:0000ff",
+            "--spacetime-depth=16",
+            "--spacing=3",
+            "--window-width=800",
+            "--window-height=600",
+        ])?;
+        let expected = GameConfig::new(64, 32)?
+            .with_backend(Backend::Sparse)
+            .with_rule("B36/S23".parse()?)
+            .with_seed(7)
+            .with_density(0.25)?
+            .with_pattern("glider.rle")
+            .with_tick(Duration::from_millis(100))?
+            .with_paused(false)
+            .with_gradient("0:ffffff,4:0000ff".parse()?)
+            .with_spacetime_depth(16)?
+            .with_spacing(3.)?;
+        assert_eq!(cli.config()?, expected);
+        assert_eq!(cli.session_file().path().to_str(), Some("saved.session"));
+        assert_eq!(cli.window_size(), (800., 600.));
+
+        assert!(Cli::try_parse_from(["conway", "--rule=B9"]).is_err());
+        assert!(Cli::try_parse_from(["conway", "--backend=dense"]).is_err());
+        assert!(Cli::try_parse_from(["conway", "--gradient=4:ffffff,0:000000"]).is_err());
+        assert!(Cli::try_parse_from(["conway", "--width=2"])?
+            .config()
+            .is_err());
+        assert!(Cli::try_parse_from(["conway", "--tick-ms=0"])?
+            .config()
+            .is_err());
+        assert!(Cli::try_parse_from(["conway", "--spacetime-depth=0"])?
+            .config()
+            .is_err());
+        assert!(Cli::try_parse_from(["conway", "--spacing=1"])?
+            .config()
+            .is_err());
+        Ok(())
+    }
+
+    /// Resuming a session builds the board at the size it was saved at
+    #[test]
+    fn sessions_set_the_board_size() -> anyhow::Result<()> {
+        let path = env::temp_dir().join("conway_cli_resume.session");
+        let session = Session {
+            cells: BitBoard::new(40, 20),
+            rule: Default::default(),
+            topology: Default::default(),
+            generation: 3,
+            tick: Duration::from_millis(250),
+            paused: false,
+            camera: Transform::default(),
+            yaw: 0.,
+            pitch: 0.,
+        };
+        save_session(&path, &session)?;
+        let arg = path.to_str().expect("Temp paths are UTF-8");
+        let resumed = Cli::try_parse_from(["conway", arg, "--width=64"])?.config();
+        fs::remove_file(&path)?;
+        let resumed = resumed?;
+        assert_eq!((resumed.width(), resumed.height()), (40, 20));
+        Ok(())
+    }
+}
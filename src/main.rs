@@ -1,30 +1,90 @@
 use bevy::prelude::*;
-use conway::scene::{
-    interaction::{display_controls, ego_camera, handle_click, hide_cursor, keyboard_motion},
-    world::{init_conway_grid, next_game_tick, setup_world},
+use clap::Parser;
+use conway::{
+    cli::Cli,
+    scene::{
+        config::{backend_is, Backend},
+        edit::{aim_editor, paint_cells, setup_editor},
+        interaction::{
+            cycle_settle_action, display_controls, display_soup, ego_camera, handle_click,
+            hide_cursor, keyboard_motion, step_generations,
+        },
+        select::{select_region, setup_selection},
+        session::{resume_session, save_or_load},
+        sim::HashLife,
+        spacetime::stack_generations,
+        sparse::SparseGol,
+        stamp::{pick_pattern, setup_stamp, stamp_pattern},
+        undo::track_edits,
+        world::{
+            init_conway_grid, init_hashlife_grid, init_sparse_grid, next_game_tick,
+            next_universe_tick, setup_world,
+        },
+        BoardSettled,
+    },
 };
 
-/// Run's Conway's Game of Life in 3d
-
+/// Run's Conway's Game of Life in 3d. Pass a session file to resume it, or
+/// see `--help` for how to start a new game.
 fn main() {
+    let cli = Cli::parse();
+    let config = match cli.config() {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("error: {err:#}");
+            std::process::exit(2);
+        }
+    };
     App::new()
-        .add_plugins(DefaultPlugins)
+        .add_plugins(DefaultPlugins.set(WindowPlugin {
+            primary_window: Some(Window {
+                resolution: cli.window_size().into(),
+                ..default()
+            }),
+            ..default()
+        }))
         .insert_resource(ClearColor(Color::Rgba {
             red: 0.,
             green: 0.,
             blue: 0.,
             alpha: 0.5,
         }))
+        .insert_resource(config)
+        .insert_resource(cli.session_file())
+        .add_event::<BoardSettled>()
         .add_systems(
             Startup,
-            (hide_cursor, setup_world, init_conway_grid, display_controls),
+            (
+                hide_cursor,
+                setup_world,
+                init_conway_grid.run_if(backend_is(Backend::Bounded)),
+                init_sparse_grid.run_if(backend_is(Backend::Sparse)),
+                init_hashlife_grid.run_if(backend_is(Backend::HashLife)),
+                setup_editor,
+                setup_stamp,
+                setup_selection,
+                display_controls,
+                resume_session.after(setup_world).after(init_conway_grid),
+            ),
         )
         .add_systems(
             Update,
             (
-                (ego_camera, keyboard_motion).chain(),
-                next_game_tick,
+                (ego_camera, keyboard_motion, pick_pattern, aim_editor).chain(),
+                (
+                    step_generations,
+                    (paint_cells, stamp_pattern, select_region).after(aim_editor),
+                    next_game_tick,
+                    track_edits,
+                )
+                    .chain(),
+                next_universe_tick::<SparseGol>,
+                next_universe_tick::<HashLife>,
                 handle_click,
+                save_or_load,
+                cycle_settle_action,
+                display_soup.after(next_game_tick),
+                stack_generations.after(next_game_tick),
                 bevy::window::close_on_esc,
             ),
         )
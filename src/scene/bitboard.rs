@@ -0,0 +1,244 @@
+use anyhow::bail;
+
+/// Number of cells packed into each word of a board row
+pub const WORD_BITS: usize = u64::BITS as usize;
+
+/// A dense grid of cells packed 64 to a word. Every row starts on a fresh
+/// word, and bits past the right edge of a row are always zero.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitBoard {
+    width: usize,
+    height: usize,
+    words_per_row: usize,
+    words: Vec<u64>,
+}
+
+impl BitBoard {
+    /// Returns an all-dead board with the given dimensions.
+    pub fn new(width: usize, height: usize) -> Self {
+        let words_per_row = width.div_ceil(WORD_BITS);
+        BitBoard {
+            width,
+            height,
+            words_per_row,
+            words: vec![0; words_per_row * height],
+        }
+    }
+
+    /// Packs a row-major grid of cells into a board.
+    /// Errors if the rows are not all the same length.
+    pub fn from_rows(rows: &[Vec<bool>]) -> anyhow::Result<Self> {
+        let width = rows.first().map_or(0, |row| row.len());
+        let mut board = BitBoard::new(width, rows.len());
+        for (row_ind, row) in rows.iter().enumerate() {
+            if row.len() != width {
+                bail!(
+                    "Row {row_ind} has {} cells, but the board is {width} cells wide",
+                    row.len()
+                );
+            }
+            for (col, is_alive) in row.iter().enumerate() {
+                if *is_alive {
+                    board.set(row_ind, col, true);
+                }
+            }
+        }
+        Ok(board)
+    }
+
+    /// Unpacks the board into a row-major grid of cells.
+    pub fn to_rows(&self) -> Vec<Vec<bool>> {
+        (0..self.height)
+            .map(|row| (0..self.width).map(|col| self.get(row, col)).collect())
+            .collect()
+    }
+
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    #[inline]
+    pub fn words_per_row(&self) -> usize {
+        self.words_per_row
+    }
+
+    /// Whether the cell at row, col is alive.
+    /// Panics if row, col are out of bounds.
+    #[inline]
+    pub fn get(&self, row: usize, col: usize) -> bool {
+        assert!(row < self.height && col < self.width);
+        let word = self.words[row * self.words_per_row + col / WORD_BITS];
+        word >> (col % WORD_BITS) & 1 == 1
+    }
+
+    /// Sets whether the cell at row, col is alive.
+    /// Panics if row, col are out of bounds.
+    #[inline]
+    pub fn set(&mut self, row: usize, col: usize, is_alive: bool) {
+        assert!(row < self.height && col < self.width);
+        let word = &mut self.words[row * self.words_per_row + col / WORD_BITS];
+        let mask = 1 << (col % WORD_BITS);
+        if is_alive {
+            *word |= mask;
+        } else {
+            *word &= !mask;
+        }
+    }
+
+    /// The packed words of one row. Column c is bit c % 64 of word c / 64.
+    #[inline]
+    pub fn row(&self, row: usize) -> &[u64] {
+        let start = row * self.words_per_row;
+        &self.words[start..(start + self.words_per_row)]
+    }
+
+    /// Mutable access to the packed words of one row. Callers must leave bits
+    /// past the right edge of the board unset.
+    #[inline]
+    pub fn row_mut(&mut self, row: usize) -> &mut [u64] {
+        let start = row * self.words_per_row;
+        &mut self.words[start..(start + self.words_per_row)]
+    }
+
+    /// The packed words of every row, one row after another.
+    #[inline]
+    pub fn words(&self) -> &[u64] {
+        &self.words
+    }
+
+    /// Mutable access to the packed words of every row, one row after another.
+    /// Callers must leave bits past the right edge of the board unset.
+    #[inline]
+    pub fn words_mut(&mut self) -> &mut [u64] {
+        &mut self.words
+    }
+
+    /// A mask of the bits in word `word_ind` of a row that are on the board.
+    #[inline]
+    pub fn word_mask(&self, word_ind: usize) -> u64 {
+        let cols_left = self.width - word_ind * WORD_BITS;
+        if cols_left >= WORD_BITS {
+            u64::MAX
+        } else {
+            (1 << cols_left) - 1
+        }
+    }
+
+    /// Copies a rectangle of the board into a new board.
+    /// Panics if the rectangle extends past the board.
+    pub fn crop(&self, top: usize, left: usize, width: usize, height: usize) -> BitBoard {
+        assert!(top + height <= self.height && left + width <= self.width);
+        let mut cropped = BitBoard::new(width, height);
+        for row in 0..height {
+            for col in 0..width {
+                if self.get(top + row, left + col) {
+                    cropped.set(row, col, true);
+                }
+            }
+        }
+        cropped
+    }
+
+    /// Returns a copy of the board turned a quarter turn clockwise.
+    pub fn rotated(&self) -> BitBoard {
+        let mut turned = BitBoard::new(self.height, self.width);
+        for (row, col) in self.iter_alive() {
+            turned.set(col, self.height - 1 - row, true);
+        }
+        turned
+    }
+
+    /// Returns a copy of the board mirrored left to right.
+    pub fn flipped(&self) -> BitBoard {
+        let mut mirrored = BitBoard::new(self.width, self.height);
+        for (row, col) in self.iter_alive() {
+            mirrored.set(row, self.width - 1 - col, true);
+        }
+        mirrored
+    }
+
+    /// Number of live cells on the board.
+    pub fn population(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    /// Kills every cell on the board.
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    /// Iterates the row, col positions of every live cell in row-major order.
+    pub fn iter_alive(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.words.iter().enumerate().flat_map(move |(ind, word)| {
+            let row = ind / self.words_per_row;
+            let base_col = (ind % self.words_per_row) * WORD_BITS;
+            let mut word = *word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some((row, base_col + bit))
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod bitboard_tests {
+    use super::BitBoard;
+
+    #[test]
+    fn get_set_across_words() {
+        let mut board = BitBoard::new(130, 3);
+        assert_eq!(board.words_per_row(), 3);
+        for col in [0, 63, 64, 127, 128, 129] {
+            board.set(1, col, true);
+            assert!(board.get(1, col));
+        }
+        assert_eq!(board.population(), 6);
+        assert_eq!(
+            board.iter_alive().collect::<Vec<_>>(),
+            vec![(1, 0), (1, 63), (1, 64), (1, 127), (1, 128), (1, 129)]
+        );
+        board.set(1, 64, false);
+        assert!(!board.get(1, 64));
+        assert_eq!(board.row(1)[2], 0b11);
+        assert_eq!(board.word_mask(2), 0b11);
+    }
+
+    #[test]
+    fn rows_round_trip() -> anyhow::Result<()> {
+        let rows = vec![
+            vec![true, false, true],
+            vec![false, false, true],
+            vec![true, true, false],
+        ];
+        let board = BitBoard::from_rows(&rows)?;
+        assert_eq!(board.to_rows(), rows);
+        assert!(BitBoard::from_rows(&[vec![true], vec![true, false]]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn rotates_and_flips() -> anyhow::Result<()> {
+        let rows = |text: &str| -> Vec<Vec<bool>> {
+            text.split('/')
+                .map(|row| row.chars().map(|c| c == 'O').collect())
+                .collect()
+        };
+        let board = BitBoard::from_rows(&rows("OO./..O"))?;
+        assert_eq!(board.rotated().to_rows(), rows(".O/.O/O."));
+        assert_eq!(board.flipped().to_rows(), rows(".OO/O.."));
+        assert_eq!(board.rotated().rotated().rotated().rotated(), board);
+        assert_eq!(board.flipped().flipped(), board);
+        Ok(())
+    }
+}
@@ -0,0 +1,280 @@
+use super::{
+    rule::Rule,
+    sim::{Bounds, ConwayGol, Universe},
+    sparse::SparseGol,
+};
+use apgcode::{canonical_wechsler, normalize};
+use std::collections::{BTreeMap, HashSet, VecDeque};
+
+pub mod apgcode;
+pub mod classify;
+
+/// Longest period `take_census` looks for when identifying an object
+pub const CENSUS_MAX_PERIOD: u64 = 64;
+
+/// How far apart two live cells can be while still counting as parts of the
+/// same object. Cells two apart share a neighbor, so they can interact.
+const OBJECT_REACH: i64 = 2;
+
+/// The kinds of objects that repeat themselves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectKind {
+    StillLife,
+    Oscillator {
+        period: u64,
+    },
+    /// Reappears `d_row`, `d_col` away from where it was `period`
+    /// generations earlier
+    Spaceship {
+        period: u64,
+        d_row: i64,
+        d_col: i64,
+    },
+}
+
+/// An object that `identify` recognized.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Identified {
+    pub kind: ObjectKind,
+    /// The object's name in Catagolue, like "xs4_33" for a block:
+    /// https://conwaylife.com/wiki/Apgcode
+    pub apgcode: String,
+    /// The object's cells in each generation of one period, where it was
+    /// found first
+    pub phases: Vec<Vec<(i64, i64)>>,
+}
+
+/// Counts of the objects on a board, keyed by apgcode.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Census {
+    pub counts: BTreeMap<String, usize>,
+    /// Objects that didn't settle into a known period, such as ones that are
+    /// still evolving or interacting with their neighbors
+    pub unidentified: usize,
+}
+
+impl Census {
+    /// Number of objects identified.
+    pub fn total(&self) -> usize {
+        self.counts.values().sum()
+    }
+}
+
+/// Evolves a group of cells alone on an unbounded plane under a rule, and
+/// names what it is if it repeats itself within `max_period` generations.
+/// Errors if the rule has B0, which an unbounded plane can't simulate.
+pub fn identify(
+    cells: &[(i64, i64)],
+    rule: Rule,
+    max_period: u64,
+) -> anyhow::Result<Option<Identified>> {
+    let Some(start_bounds) = Bounds::enclosing(cells.iter().copied()) else {
+        return Ok(None);
+    };
+    let mut universe = SparseGol::new().with_rule(rule)?;
+    for (row, col) in cells {
+        universe.set(*row, *col, true);
+    }
+    let (start, ..) = normalize(cells);
+    let mut phases = vec![cells.to_vec()];
+
+    for period in 1..=max_period {
+        universe.tick();
+        let now: Vec<_> = universe.iter_alive().collect();
+        let Some(bounds) = Bounds::enclosing(now.iter().copied()) else {
+            return Ok(None);
+        };
+        if normalize(&now).0 != start {
+            phases.push(now);
+            continue;
+        }
+
+        let (d_row, d_col) = (
+            bounds.top - start_bounds.top,
+            bounds.left - start_bounds.left,
+        );
+        let (kind, prefix) = match (period, d_row, d_col) {
+            (1, 0, 0) => (ObjectKind::StillLife, format!("xs{}_", cells.len())),
+            (_, 0, 0) => (ObjectKind::Oscillator { period }, format!("xp{period}_")),
+            _ => (
+                ObjectKind::Spaceship {
+                    period,
+                    d_row,
+                    d_col,
+                },
+                format!("xq{period}_"),
+            ),
+        };
+        return Ok(Some(Identified {
+            kind,
+            apgcode: prefix + &canonical_wechsler(&phases),
+            phases,
+        }));
+    }
+    Ok(None)
+}
+
+/// Splits live cells into objects: groups of cells close enough to affect
+/// each other.
+pub fn objects(cells: impl IntoIterator<Item = (i64, i64)>) -> Vec<Vec<(i64, i64)>> {
+    let mut unvisited: HashSet<_> = cells.into_iter().collect();
+    let mut found = Vec::new();
+    while let Some(&seed) = unvisited.iter().next() {
+        unvisited.remove(&seed);
+        let mut object = vec![seed];
+        let mut queue = VecDeque::from([seed]);
+        while let Some((row, col)) = queue.pop_front() {
+            for d_row in -OBJECT_REACH..=OBJECT_REACH {
+                for d_col in -OBJECT_REACH..=OBJECT_REACH {
+                    let cell = (row + d_row, col + d_col);
+                    if unvisited.remove(&cell) {
+                        object.push(cell);
+                        queue.push_back(cell);
+                    }
+                }
+            }
+        }
+        object.sort_unstable();
+        found.push(object);
+    }
+    found
+}
+
+/// Counts the still lifes, oscillators, and spaceships on a board that has
+/// settled, like apgsearch does for each soup. Objects are identified as if
+/// they were alone on an unbounded plane, so ones touching the board's
+/// edges may not be recognized.
+/// Errors if the board's rule has B0.
+pub fn take_census(gol: &ConwayGol) -> anyhow::Result<Census> {
+    let cells = gol
+        .board()
+        .iter_alive()
+        .map(|(row, col)| (row as i64, col as i64));
+    let mut census = Census::default();
+    for object in objects(cells) {
+        match identify(&object, gol.rule(), CENSUS_MAX_PERIOD)? {
+            Some(found) => *census.counts.entry(found.apgcode).or_default() += 1,
+            None => census.unidentified += 1,
+        }
+    }
+    Ok(census)
+}
+
+#[cfg(test)]
+mod census_tests {
+    use super::{identify, objects, take_census, ObjectKind};
+    use crate::scene::{bitboard::BitBoard, rule::Rule, sim::ConwayGol};
+
+    const GLIDER: &[(i64, i64)] = &[(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)];
+    const LWSS: &[(i64, i64)] = &[
+        (0, 1),
+        (0, 4),
+        (1, 0),
+        (2, 0),
+        (2, 4),
+        (3, 0),
+        (3, 1),
+        (3, 2),
+        (3, 3),
+    ];
+
+    fn code(cells: &[(i64, i64)]) -> String {
+        identify(cells, Rule::CONWAY, 16)
+            .unwrap()
+            .expect("Object should repeat")
+            .apgcode
+    }
+
+    #[test]
+    fn known_apgcodes() {
+        assert_eq!(code(&[(0, 0), (0, 1), (1, 0), (1, 1)]), "xs4_33");
+        assert_eq!(code(&[(5, 5), (6, 5), (7, 5)]), "xp2_7");
+        assert_eq!(code(GLIDER), "xq4_153");
+        assert_eq!(code(LWSS), "xq4_6frc");
+        let beehive = [(0, 1), (0, 2), (1, 0), (1, 3), (2, 1), (2, 2)];
+        assert_eq!(code(&beehive), "xs6_696");
+        let loaf = [(0, 1), (0, 2), (1, 0), (1, 3), (2, 1), (2, 3), (3, 2)];
+        assert_eq!(code(&loaf), "xs7_2596");
+        let toad = [(0, 1), (0, 2), (0, 3), (1, 0), (1, 1), (1, 2)];
+        assert_eq!(code(&toad), "xp2_7e");
+    }
+
+    #[test]
+    fn kinds() -> anyhow::Result<()> {
+        let glider = identify(GLIDER, Rule::CONWAY, 16)?.unwrap();
+        assert_eq!(
+            glider.kind,
+            ObjectKind::Spaceship {
+                period: 4,
+                d_row: 1,
+                d_col: 1
+            }
+        );
+        assert_eq!(glider.phases.len(), 4);
+
+        let blinker = identify(&[(0, 0), (0, 1), (0, 2)], Rule::CONWAY, 16)?.unwrap();
+        assert_eq!(blinker.kind, ObjectKind::Oscillator { period: 2 });
+        // A lone pair of cells dies, and an R-pentomino takes too long
+        assert_eq!(identify(&[(0, 0), (0, 1)], Rule::CONWAY, 16)?, None);
+        let r_pentomino = [(0, 1), (0, 2), (1, 0), (1, 1), (2, 1)];
+        assert_eq!(identify(&r_pentomino, Rule::CONWAY, 16)?, None);
+        assert!(identify(GLIDER, "B03/S23".parse()?, 16).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn separates_objects() {
+        // A beacon's two blocks touch corners, and a gap of three cells
+        // separates the others
+        let cells = [
+            (0, 0),
+            (0, 1),
+            (1, 0),
+            (2, 3),
+            (3, 2),
+            (3, 3),
+            (0, 7),
+            (8, 0),
+        ];
+        let found = objects(cells);
+        assert_eq!(found.len(), 3);
+        assert!(found.iter().any(|object| object.len() == 6));
+    }
+
+    #[test]
+    fn settled_soup() -> anyhow::Result<()> {
+        let mut board = BitBoard::new(64, 48);
+        let mut place = |cells: &[(i64, i64)], top: usize, left: usize| {
+            for (row, col) in cells {
+                board.set(top + *row as usize, left + *col as usize, true);
+            }
+        };
+        let block = [(0, 0), (0, 1), (1, 0), (1, 1)];
+        place(&block, 2, 2);
+        place(&block, 2, 20);
+        place(&[(0, 0), (0, 1), (0, 2)], 10, 10);
+        place(&[(0, 1), (0, 2), (1, 0), (1, 3), (2, 1), (2, 2)], 20, 30);
+        place(GLIDER, 30, 5);
+        place(LWSS, 38, 40);
+        // Two cells that die
+        place(&[(0, 0), (0, 1)], 40, 10);
+        let census = take_census(&ConwayGol::from_board(board))?;
+
+        let expected = [
+            ("xp2_7", 1),
+            ("xq4_153", 1),
+            ("xq4_6frc", 1),
+            ("xs4_33", 2),
+            ("xs6_696", 1),
+        ];
+        let counts: Vec<_> = census
+            .counts
+            .iter()
+            .map(|(code, ct)| (code.as_str(), *ct))
+            .collect();
+        assert_eq!(counts, expected);
+        assert_eq!(census.total(), 6);
+        assert_eq!(census.unidentified, 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,136 @@
+/// Characters for the 32 values a 5 cell column of a strip can take
+const COLUMN_CHARS: &[u8; 32] = b"0123456789abcdefghijklmnopqrstuv";
+
+/// Characters following 'y' for runs of 4 to 39 blank columns
+const RUN_CHARS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// Rows in each strip of the extended Wechsler format
+const STRIP_ROWS: i64 = 5;
+
+/// Encodes a set of cells in the extended Wechsler format apgcodes use:
+/// https://conwaylife.com/wiki/Apgcode
+/// The cells are cut into strips 5 rows tall, and each column of a strip
+/// becomes one character whose bits are its cells from the top down. Strips
+/// are separated by 'z' and runs of blank columns are shortened to 'w', 'x',
+/// or 'y' and a count. The cells are shifted so that their bounding box
+/// starts at row 0, col 0.
+pub fn wechsler(cells: &[(i64, i64)]) -> String {
+    let (cells, height, width) = normalize(cells);
+    let mut strips =
+        vec![vec![0u8; width as usize]; (height as usize).div_ceil(STRIP_ROWS as usize)];
+    for (row, col) in cells {
+        strips[(row / STRIP_ROWS) as usize][col as usize] |= 1 << (row % STRIP_ROWS);
+    }
+
+    let mut out = String::new();
+    for (ind, strip) in strips.iter().enumerate() {
+        if ind > 0 {
+            out.push('z');
+        }
+        let len = strip.iter().rposition(|col| *col != 0).map_or(0, |i| i + 1);
+        let mut blank_run = 0;
+        for col in &strip[..len] {
+            if *col == 0 {
+                blank_run += 1;
+                continue;
+            }
+            push_blank_run(&mut out, blank_run);
+            blank_run = 0;
+            out.push(COLUMN_CHARS[*col as usize] as char);
+        }
+    }
+    out
+}
+
+/// Writes a run of blank columns as compactly as the format allows.
+fn push_blank_run(out: &mut String, mut run: usize) {
+    while run > 0 {
+        let len = run.min(39);
+        match len {
+            1 => out.push('0'),
+            2 => out.push('w'),
+            3 => out.push('x'),
+            _ => {
+                out.push('y');
+                out.push(RUN_CHARS[len - 4] as char);
+            }
+        }
+        run -= len;
+    }
+}
+
+/// Picks the canonical code for an object from its phases: the shortest
+/// extended Wechsler code over every phase in every rotation and reflection,
+/// with ties going to the first in lexicographic order.
+pub fn canonical_wechsler(phases: &[Vec<(i64, i64)>]) -> String {
+    phases
+        .iter()
+        .flat_map(|phase| orientations(phase))
+        .map(|cells| wechsler(&cells))
+        .min_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)))
+        .unwrap_or_default()
+}
+
+/// The eight rotations and reflections of a set of cells.
+pub fn orientations(cells: &[(i64, i64)]) -> [Vec<(i64, i64)>; 8] {
+    let transform = |f: fn(i64, i64) -> (i64, i64)| {
+        cells
+            .iter()
+            .map(|(row, col)| f(*row, *col))
+            .collect::<Vec<_>>()
+    };
+    [
+        transform(|r, c| (r, c)),
+        transform(|r, c| (c, -r)),
+        transform(|r, c| (-r, -c)),
+        transform(|r, c| (-c, r)),
+        transform(|r, c| (r, -c)),
+        transform(|r, c| (-r, c)),
+        transform(|r, c| (c, r)),
+        transform(|r, c| (-c, -r)),
+    ]
+}
+
+/// Shifts cells so their bounding box starts at row 0, col 0, sorted in
+/// row-major order. Returns the cells along with the box's height and width.
+pub fn normalize(cells: &[(i64, i64)]) -> (Vec<(i64, i64)>, i64, i64) {
+    let top = cells.iter().map(|(row, _)| *row).min().unwrap_or(0);
+    let left = cells.iter().map(|(_, col)| *col).min().unwrap_or(0);
+    let mut shifted: Vec<_> = cells
+        .iter()
+        .map(|(row, col)| (row - top, col - left))
+        .collect();
+    shifted.sort_unstable();
+    let height = shifted.iter().map(|(row, _)| row + 1).max().unwrap_or(0);
+    let width = shifted.iter().map(|(_, col)| col + 1).max().unwrap_or(0);
+    (shifted, height, width)
+}
+
+#[cfg(test)]
+mod apgcode_tests {
+    use super::{canonical_wechsler, wechsler};
+
+    #[test]
+    fn strips_and_blank_runs() {
+        assert_eq!(wechsler(&[(0, 0), (0, 1), (1, 0), (1, 1)]), "33");
+        assert_eq!(wechsler(&[(0, 0), (0, 3)]), "1w1");
+        assert_eq!(wechsler(&[(0, 0), (0, 4)]), "1x1");
+        assert_eq!(wechsler(&[(0, 0), (0, 5)]), "1y01");
+        assert_eq!(wechsler(&[(0, 0), (0, 44)]), "1yzy01");
+        // Rows 5 and up start a new strip, and blank strips leave empty gaps
+        assert_eq!(wechsler(&[(0, 0), (4, 1), (5, 2)]), "1gzw1");
+        assert_eq!(wechsler(&[(0, 0), (10, 0)]), "1zz1");
+        assert_eq!(wechsler(&[]), "");
+    }
+
+    #[test]
+    fn canonical_orientation() {
+        let blinker = vec![(0, 0), (0, 1), (0, 2)];
+        assert_eq!(canonical_wechsler(&[blinker]), "7");
+        // A boat in any orientation has the same code
+        let boat = vec![(0, 0), (0, 1), (1, 0), (1, 2), (2, 1)];
+        let flipped: Vec<_> = boat.iter().map(|(r, c)| (2 - c, *r)).collect();
+        assert_eq!(canonical_wechsler(&[boat]), "253");
+        assert_eq!(canonical_wechsler(&[flipped]), "253");
+    }
+}
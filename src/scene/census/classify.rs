@@ -0,0 +1,261 @@
+use super::{identify, Identified, ObjectKind, CENSUS_MAX_PERIOD};
+use crate::scene::{rule::Rule, sim::Universe, sparse::SparseGol};
+use std::{collections::HashSet, fmt};
+
+/// Generations a pattern's population has to keep repeating before it
+/// counts as stabilized
+const SETTLE_GENERATIONS: usize = 256;
+
+/// What a pattern turns out to be when run alone on an unbounded plane.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Classification {
+    StillLife {
+        population: usize,
+    },
+    Oscillator {
+        period: u64,
+        /// Average number of cells that change state each generation
+        heat: f64,
+        /// Fraction of the cells the oscillator ever uses that aren't alive
+        /// in every phase
+        volatility: f64,
+    },
+    Spaceship {
+        period: u64,
+        /// How far the ship travels each period
+        d_row: i64,
+        d_col: i64,
+        speed: Speed,
+    },
+    /// Evolves for a while before settling into still lifes, oscillators,
+    /// and escaping spaceships
+    Methuselah {
+        stabilized_at: u64,
+        final_population: usize,
+    },
+    /// Every cell died by this generation
+    DiesOut {
+        generation: u64,
+    },
+    /// Still evolving when the classifier gave up
+    Unsettled,
+}
+
+/// How fast a spaceship travels, as a fraction of the speed of light: one
+/// cell per generation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Speed {
+    pub cells: u64,
+    pub generations: u64,
+}
+
+impl Speed {
+    /// Returns the speed of travelling `cells` in `generations`, in lowest
+    /// terms.
+    pub fn new(cells: u64, generations: u64) -> Self {
+        let divisor = gcd(cells, generations).max(1);
+        Speed {
+            cells: cells / divisor,
+            generations: generations / divisor,
+        }
+    }
+}
+
+impl fmt::Display for Speed {
+    /// Writes the speed the way Life enthusiasts do, like "c/4" or "2c/5".
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.cells != 1 {
+            write!(f, "{}", self.cells)?;
+        }
+        write!(f, "c")?;
+        if self.generations != 1 {
+            write!(f, "/{}", self.generations)?;
+        }
+        Ok(())
+    }
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Runs a pattern alone on an unbounded plane under a rule for up to
+/// `max_generations` and classifies it. Patterns that don't repeat
+/// themselves right away are watched until their population repeats for a
+/// while, which allows for spaceships escaping what's left behind.
+/// Errors if the rule has B0, which an unbounded plane can't simulate.
+pub fn classify(
+    cells: &[(i64, i64)],
+    rule: Rule,
+    max_generations: u64,
+) -> anyhow::Result<Classification> {
+    if let Some(found) = identify(cells, rule, CENSUS_MAX_PERIOD)? {
+        return Ok(repeating(&found));
+    }
+
+    let mut universe = SparseGol::new().with_rule(rule)?;
+    for (row, col) in cells {
+        universe.set(*row, *col, true);
+    }
+    let mut populations = vec![cells.len()];
+    // For each period, how many generations in a row the population has
+    // matched the population that many generations earlier
+    let mut runs = vec![0; CENSUS_MAX_PERIOD as usize + 1];
+    for generation in 1..=max_generations {
+        universe.tick();
+        let population = universe.population();
+        if population == 0 {
+            return Ok(Classification::DiesOut { generation });
+        }
+        populations.push(population);
+
+        let now = populations.len() - 1;
+        for (period, run) in runs.iter_mut().enumerate().skip(1) {
+            if period <= now && populations[now - period] == population {
+                *run += 1;
+            } else {
+                *run = 0;
+            }
+            if *run >= SETTLE_GENERATIONS {
+                let stabilized_at = now + 1 - *run - period;
+                return Ok(Classification::Methuselah {
+                    stabilized_at: stabilized_at as u64,
+                    final_population: populations[stabilized_at],
+                });
+            }
+        }
+    }
+    Ok(Classification::Unsettled)
+}
+
+/// Measures an object that repeats itself.
+fn repeating(found: &Identified) -> Classification {
+    match found.kind {
+        ObjectKind::StillLife => Classification::StillLife {
+            population: found.phases[0].len(),
+        },
+        ObjectKind::Oscillator { period } => {
+            let phases: Vec<HashSet<_>> = found
+                .phases
+                .iter()
+                .map(|phase| phase.iter().copied().collect())
+                .collect();
+            let changes: usize = phases
+                .iter()
+                .zip(phases.iter().cycle().skip(1))
+                .map(|(now, next)| now.symmetric_difference(next).count())
+                .sum();
+            let ever_alive: HashSet<_> = phases.iter().flatten().copied().collect();
+            let always_alive = ever_alive
+                .iter()
+                .filter(|cell| phases.iter().all(|phase| phase.contains(cell)))
+                .count();
+            Classification::Oscillator {
+                period,
+                heat: changes as f64 / period as f64,
+                volatility: (ever_alive.len() - always_alive) as f64 / ever_alive.len() as f64,
+            }
+        }
+        ObjectKind::Spaceship {
+            period,
+            d_row,
+            d_col,
+        } => Classification::Spaceship {
+            period,
+            d_row,
+            d_col,
+            speed: Speed::new(d_row.unsigned_abs().max(d_col.unsigned_abs()), period),
+        },
+    }
+}
+
+#[cfg(test)]
+mod classify_tests {
+    use super::{classify, Classification, Speed};
+    use crate::scene::rule::Rule;
+
+    fn run(cells: &[(i64, i64)]) -> Classification {
+        classify(cells, Rule::CONWAY, 2000).unwrap()
+    }
+
+    #[test]
+    fn blinker() {
+        let Classification::Oscillator {
+            period,
+            heat,
+            volatility,
+        } = run(&[(0, 0), (0, 1), (0, 2)])
+        else {
+            panic!("A blinker should oscillate");
+        };
+        assert_eq!(period, 2);
+        // Four cells flip each generation, and only the middle one stays
+        assert_eq!(heat, 4.0);
+        assert_eq!(volatility, 0.8);
+        let block = run(&[(0, 0), (0, 1), (1, 0), (1, 1)]);
+        assert_eq!(block, Classification::StillLife { population: 4 });
+    }
+
+    #[test]
+    fn glider_and_lwss() {
+        let glider = run(&[(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+        assert_eq!(
+            glider,
+            Classification::Spaceship {
+                period: 4,
+                d_row: 1,
+                d_col: 1,
+                speed: Speed::new(1, 4),
+            }
+        );
+        let lwss = [
+            (0, 1),
+            (0, 4),
+            (1, 0),
+            (2, 0),
+            (2, 4),
+            (3, 0),
+            (3, 1),
+            (3, 2),
+            (3, 3),
+        ];
+        let Classification::Spaceship {
+            period,
+            d_row,
+            d_col,
+            speed,
+        } = run(&lwss)
+        else {
+            panic!("An LWSS should be a spaceship");
+        };
+        assert_eq!((period, d_row, d_col.abs()), (4, 0, 2));
+        assert_eq!(speed.to_string(), "c/2");
+        assert_eq!(Speed::new(4, 10).to_string(), "2c/5");
+        assert_eq!(Speed::new(3, 3).to_string(), "c");
+    }
+
+    #[test]
+    fn r_pentomino() {
+        let r_pentomino = [(0, 1), (0, 2), (1, 0), (1, 1), (2, 1)];
+        assert_eq!(
+            run(&r_pentomino),
+            Classification::Methuselah {
+                stabilized_at: 1103,
+                final_population: 116,
+            }
+        );
+        assert_eq!(
+            classify(&r_pentomino, Rule::CONWAY, 500).unwrap(),
+            Classification::Unsettled
+        );
+        // A diagonal of three cells dies in two generations
+        assert_eq!(
+            run(&[(0, 0), (1, 1), (2, 2)]),
+            Classification::DiesOut { generation: 2 }
+        );
+    }
+}
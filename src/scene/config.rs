@@ -0,0 +1,437 @@
+use super::{
+    bitboard::BitBoard,
+    gradient::AgeGradient,
+    pattern::{load_hashlife, load_pattern, PatternFormat},
+    rule::Rule,
+    sim::{ConwayGol, HashLife},
+    soup::Soup,
+    spacetime::DEFAULT_SPACETIME_DEPTH,
+    sparse::SparseGol,
+};
+use anyhow::{bail, Context};
+use bevy::ecs::system::{Res, Resource};
+use std::{fmt, path::PathBuf, str::FromStr, time::Duration};
+
+/// Cells along each side of the board by default. Only the middle half of
+/// each side is in view, which keeps the edges of the view looking alive.
+pub const DEFAULT_BOARD_WIDTH: usize = 2usize.pow(8);
+pub const DEFAULT_BOARD_HEIGHT: usize = 2usize.pow(8);
+
+/// Time between ticks by default
+pub const DEFAULT_TICK: Duration = Duration::from_millis(500);
+
+/// Fraction of cells alive in the starting soup by default
+pub const DEFAULT_DENSITY: f64 = 0.5;
+
+/// Distance between neighboring cubes by default, leaving a thin gap
+/// between the 2 unit wide cubes
+pub const DEFAULT_CUBE_SPACING: f32 = 2.25;
+
+/// The simulation a game runs on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Backend {
+    /// A `ConwayGol` board with edges, which every editing tool works on
+    #[default]
+    Bounded,
+    /// An unbounded `SparseGol` universe shown around the camera
+    Sparse,
+    /// An unbounded `HashLife` universe shown around the camera, which
+    /// holds patterns far too large for a board
+    HashLife,
+}
+
+impl FromStr for Backend {
+    type Err = anyhow::Error;
+
+    /// Parses the names `Backend` displays as, case insensitive.
+    fn from_str(name: &str) -> anyhow::Result<Self> {
+        let backend = match name.trim().to_ascii_lowercase().as_str() {
+            "bounded" => Backend::Bounded,
+            "sparse" => Backend::Sparse,
+            "hashlife" => Backend::HashLife,
+            _ => bail!("Unknown backend {name:?}"),
+        };
+        Ok(backend)
+    }
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Backend::Bounded => "bounded",
+            Backend::Sparse => "sparse",
+            Backend::HashLife => "hashlife",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Run condition for systems that only apply to one backend.
+pub fn backend_is(backend: Backend) -> impl Fn(Res<GameConfig>) -> bool + Clone {
+    move |config: Res<GameConfig>| config.backend() == backend
+}
+
+/// The settings a game starts from
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct GameConfig {
+    /// The backend to run, or None to run HashLife for Macrocell patterns
+    /// and a bounded board otherwise
+    backend: Option<Backend>,
+    /// Size of the board, or of the region around the camera shown from
+    /// an unbounded universe
+    width: usize,
+    height: usize,
+    /// The rule to follow, or None to follow the pattern's rule, if any,
+    /// and B3/S23 otherwise
+    rule: Option<Rule>,
+    /// The seed of the starting soup, or None for a random one
+    seed: Option<u64>,
+    density: f64,
+    /// A pattern file to start from in place of a soup
+    pattern: Option<PathBuf>,
+    tick: Duration,
+    paused: bool,
+    /// Colors of live cells by age
+    gradient: AgeGradient,
+    /// Past generations the spacetime view stacks below the board
+    spacetime_depth: usize,
+    /// Distance between neighboring cubes
+    spacing: f32,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        GameConfig {
+            backend: None,
+            width: DEFAULT_BOARD_WIDTH,
+            height: DEFAULT_BOARD_HEIGHT,
+            rule: None,
+            seed: None,
+            density: DEFAULT_DENSITY,
+            pattern: None,
+            tick: DEFAULT_TICK,
+            paused: true,
+            gradient: AgeGradient::default(),
+            spacetime_depth: DEFAULT_SPACETIME_DEPTH,
+            spacing: DEFAULT_CUBE_SPACING,
+        }
+    }
+}
+
+impl GameConfig {
+    /// Returns the default settings with a width x height board.
+    /// Errors if either side is less than four cells.
+    pub fn new(width: usize, height: usize) -> anyhow::Result<Self> {
+        if width < 4 || height < 4 {
+            bail!("Board dimensions must be greater than 3, not {width}x{height}");
+        }
+        Ok(GameConfig {
+            width,
+            height,
+            ..GameConfig::default()
+        })
+    }
+
+    pub fn with_backend(mut self, backend: Backend) -> Self {
+        self.backend = Some(backend);
+        self
+    }
+
+    pub fn with_rule(mut self, rule: Rule) -> Self {
+        self.rule = Some(rule);
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Sets the chance each cell of the starting soup is alive.
+    /// Errors if the density isn't between 0 and 1.
+    pub fn with_density(mut self, density: f64) -> anyhow::Result<Self> {
+        if !(0. ..=1.).contains(&density) {
+            bail!("Soup density must be between 0 and 1, not {density}");
+        }
+        self.density = density;
+        Ok(self)
+    }
+
+    pub fn with_pattern(mut self, path: impl Into<PathBuf>) -> Self {
+        self.pattern = Some(path.into());
+        self
+    }
+
+    /// Sets the time between ticks.
+    /// Errors if it's zero, which would tick on every frame without end.
+    pub fn with_tick(mut self, tick: Duration) -> anyhow::Result<Self> {
+        if tick.is_zero() {
+            bail!("The tick interval must be longer than zero");
+        }
+        self.tick = tick;
+        Ok(self)
+    }
+
+    pub fn with_paused(mut self, paused: bool) -> Self {
+        self.paused = paused;
+        self
+    }
+
+    pub fn with_gradient(mut self, gradient: AgeGradient) -> Self {
+        self.gradient = gradient;
+        self
+    }
+
+    /// Sets how many past generations the spacetime view shows.
+    /// Errors if it's zero, which would leave the view empty.
+    pub fn with_spacetime_depth(mut self, depth: usize) -> anyhow::Result<Self> {
+        if depth == 0 {
+            bail!("The spacetime view must show at least one past generation");
+        }
+        self.spacetime_depth = depth;
+        Ok(self)
+    }
+
+    /// Sets the distance between neighboring cubes.
+    /// Errors if it's under 2, which would overlap the cubes.
+    pub fn with_spacing(mut self, spacing: f32) -> anyhow::Result<Self> {
+        if !(spacing.is_finite() && spacing >= 2.) {
+            bail!("Cubes must be spaced at least 2 apart, not {spacing}");
+        }
+        self.spacing = spacing;
+        Ok(self)
+    }
+
+    /// The backend to run. Patterns with a Macrocell extension run on
+    /// HashLife unless another backend was picked.
+    pub fn backend(&self) -> Backend {
+        let macrocell = self.pattern.as_ref().is_some_and(|path| {
+            PatternFormat::from_path(path).is_ok_and(|format| format == PatternFormat::Macrocell)
+        });
+        match self.backend {
+            Some(backend) => backend,
+            None if macrocell => Backend::HashLife,
+            None => Backend::default(),
+        }
+    }
+
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The columns and rows of the board in view, which is its middle.
+    #[inline]
+    pub fn view(&self) -> (usize, usize) {
+        (self.width / 2, self.height / 2)
+    }
+
+    #[inline]
+    pub fn rule(&self) -> Option<Rule> {
+        self.rule
+    }
+
+    #[inline]
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    #[inline]
+    pub fn density(&self) -> f64 {
+        self.density
+    }
+
+    #[inline]
+    pub fn pattern(&self) -> Option<&PathBuf> {
+        self.pattern.as_ref()
+    }
+
+    #[inline]
+    pub fn tick(&self) -> Duration {
+        self.tick
+    }
+
+    #[inline]
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    #[inline]
+    pub fn gradient(&self) -> &AgeGradient {
+        &self.gradient
+    }
+
+    #[inline]
+    pub fn spacetime_depth(&self) -> usize {
+        self.spacetime_depth
+    }
+
+    #[inline]
+    pub fn spacing(&self) -> f32 {
+        self.spacing
+    }
+
+    /// The soup to fill the whole board with, from the seed if there is one.
+    pub fn soup(&self) -> Soup {
+        let soup = match self.seed {
+            Some(seed) => Soup::new(seed),
+            None => Soup::random(),
+        };
+        soup.with_density(self.density)
+            .with_region(self.width, self.height)
+    }
+
+    /// Builds the board, along with the soup it started from unless it was
+    /// started from a pattern, which is centered on the board.
+    /// Errors if the pattern can't be loaded or doesn't fit on the board.
+    pub fn build(&self) -> anyhow::Result<(ConwayGol, Option<Soup>)> {
+        let Some(path) = &self.pattern else {
+            let soup = self.soup();
+            let gol = ConwayGol::build_soup(self.width, self.height, &soup)?;
+            return Ok((gol.with_rule(self.rule.unwrap_or_default()), Some(soup)));
+        };
+        let pattern = load_pattern(path)?;
+        let mut gol = ConwayGol::from_board(BitBoard::new(self.width, self.height));
+        let top = self.height.saturating_sub(pattern.cells.height()) / 2;
+        let left = self.width.saturating_sub(pattern.cells.width()) / 2;
+        gol.place(&pattern.cells, top, left)
+            .with_context(|| format!("{} doesn't fit on the board", path.display()))?;
+        let rule = self.rule.or(pattern.rule).unwrap_or_default();
+        Ok((gol.with_rule(rule), None))
+    }
+
+    /// Builds a HashLife universe starting from the same cells as `build`,
+    /// with the board's top left cell at 0, 0. Patterns aren't limited to
+    /// the board's size and are centered on the middle of the board instead.
+    /// Errors if the pattern can't be loaded, if `build` would without one,
+    /// or if the rule has B0.
+    pub fn build_hashlife(&self) -> anyhow::Result<(HashLife, Option<Soup>)> {
+        let Some(path) = &self.pattern else {
+            let (gol, soup) = self.build()?;
+            let hl = HashLife::from_board(gol.board(), 0, 0).with_rule(gol.rule())?;
+            return Ok((hl, soup));
+        };
+        let mut hl =
+            load_hashlife(path)?.centered_on(self.height as i64 / 2, self.width as i64 / 2);
+        if let Some(rule) = self.rule {
+            hl = hl.with_rule(rule)?;
+        }
+        Ok((hl, None))
+    }
+
+    /// Builds an unbounded universe starting from the same cells as `build`,
+    /// with the board's top left cell at 0, 0.
+    /// Errors if `build` would, or if the rule has B0.
+    pub fn build_sparse(&self) -> anyhow::Result<(SparseGol, Option<Soup>)> {
+        let (gol, soup) = self.build()?;
+        let sparse = SparseGol::from_board(gol.board(), 0, 0).with_rule(gol.rule())?;
+        Ok((sparse, soup))
+    }
+}
+
+#[cfg(test)]
+mod config_tests {
+    use super::{Backend, GameConfig};
+    use crate::scene::{
+        pattern::macrocell::write_macrocell,
+        sim::{Bounds, HashLife, Universe},
+    };
+    use std::{env, fs, time::Duration};
+
+    #[test]
+    fn rejects_bad_settings() -> anyhow::Result<()> {
+        assert!(GameConfig::new(3, 64).is_err());
+        assert!(GameConfig::default().with_density(1.5).is_err());
+        assert!(GameConfig::default().with_tick(Duration::ZERO).is_err());
+        assert!(GameConfig::default().with_spacetime_depth(0).is_err());
+        assert!(GameConfig::default().with_spacing(1.5).is_err());
+        assert!(GameConfig::default().with_spacing(f32::NAN).is_err());
+        assert_eq!(GameConfig::new(64, 32)?.view(), (32, 16));
+        Ok(())
+    }
+
+    #[test]
+    fn builds_seeded_soups_and_patterns() -> anyhow::Result<()> {
+        let config = GameConfig::new(32, 16)?.with_seed(9).with_density(0.25)?;
+        let (first, soup) = config.build()?;
+        let (second, _) = config.build()?;
+        assert_eq!(first.board(), second.board());
+        assert_eq!(soup.map(|soup| soup.seed()), Some(9));
+
+        let path = env::temp_dir().join("conway_config_glider.rle");
+        fs::write(&path, "x = 3, y = 3, rule = B36/S23\nbo$2bo$3o!\n")?;
+        let (gol, soup) = config.clone().with_pattern(&path).build()?;
+        fs::remove_file(&path)?;
+        assert!(soup.is_none());
+        assert_eq!(gol.board().population(), 5);
+        assert!(gol.board().get(6, 15));
+        assert_eq!(gol.rule(), "B36/S23".parse()?);
+
+        let tiny = GameConfig::new(4, 4)?.with_pattern("missing.rle");
+        assert!(tiny.build().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn builds_sparse_universes() -> anyhow::Result<()> {
+        let config = GameConfig::new(32, 16)?.with_seed(9);
+        let (gol, _) = config.build()?;
+        let (sparse, soup) = config
+            .clone()
+            .with_backend(Backend::Sparse)
+            .build_sparse()?;
+        assert!(soup.is_some());
+        assert_eq!(sparse.window(0, 0, 32, 16), *gol.board());
+        assert!(config.with_rule("B03/S23".parse()?).build_sparse().is_err());
+
+        for backend in [Backend::Bounded, Backend::Sparse, Backend::HashLife] {
+            assert_eq!(backend.to_string().parse::<Backend>()?, backend);
+        }
+        assert!("dense".parse::<Backend>().is_err());
+        Ok(())
+    }
+    /// Macrocell patterns run on HashLife, centered, however large they are
+    #[test]
+    fn builds_hashlife_universes() -> anyhow::Result<()> {
+        let mut far = HashLife::new();
+        for (row, col) in [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)] {
+            far.set(row, col, true);
+            far.set(row + 3000, col + 5000, true);
+        }
+        let path = env::temp_dir().join("conway_config_far.mc");
+        fs::write(&path, write_macrocell(&far))?;
+        let config = GameConfig::new(32, 16)?.with_pattern(&path);
+        let built = config.build_hashlife();
+        let fits = config.build();
+        fs::remove_file(&path)?;
+
+        assert_eq!(config.backend(), Backend::HashLife);
+        assert!(fits.is_err());
+        let (hl, soup) = built?;
+        assert!(soup.is_none());
+        assert_eq!(hl.population(), 10);
+        assert_eq!(
+            hl.bounds(),
+            Some(Bounds {
+                top: 8 - 1501,
+                left: 16 - 2501,
+                bottom: 8 + 1501,
+                right: 16 + 2501,
+            })
+        );
+        let bounded = config.with_backend(Backend::Bounded);
+        assert_eq!(bounded.backend(), Backend::Bounded);
+
+        let config = GameConfig::new(32, 16)?.with_seed(9);
+        let (gol, _) = config.build()?;
+        let (hl, soup) = config.build_hashlife()?;
+        assert!(soup.is_some());
+        assert_eq!(hl.window(0, 0, 32, 16), *gol.board());
+        Ok(())
+    }
+}
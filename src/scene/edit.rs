@@ -0,0 +1,248 @@
+use super::{
+    select::Selection,
+    sim::{ConwayGol, Universe},
+    stamp::Stamp,
+    CameraRotation, ChunkGrid, CubeInd, Editor,
+};
+use bevy::prelude::*;
+
+/// Marker struct for the crosshair shown in edit mode
+#[derive(Component)]
+pub struct Crosshair;
+
+/// Marker struct for the box drawn around the cell under the crosshair
+#[derive(Component)]
+pub struct Highlight;
+
+/// Spawns the crosshair and the highlight box, both hidden until edit mode
+/// is turned on.
+pub fn setup_editor(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        TextBundle {
+            text: Text::from_section(
+                "+",
+                TextStyle {
+                    font_size: 28.,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ),
+            visibility: Visibility::Hidden,
+            ..default()
+        }
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Percent(50.),
+            left: Val::Percent(50.),
+            // Roughly half the glyph, so its middle sits on the center
+            margin: UiRect::new(Val::Px(-8.), Val::ZERO, Val::Px(-17.), Val::ZERO),
+            ..default()
+        }),
+        Crosshair,
+    ));
+
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(Cuboid::new(2.3, 2.3, 2.3)),
+            material: materials.add(StandardMaterial {
+                base_color: Color::rgba(1., 1., 1., 0.3),
+                alpha_mode: AlphaMode::Blend,
+                unlit: true,
+                ..default()
+            }),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        Highlight,
+    ));
+}
+
+/// The cell under a ray, found where the ray crosses the plane through the
+/// middle of the grid's cubes. Returns None if the ray points away from the
+/// plane or crosses it outside the grid's view.
+pub fn pick_cell(
+    grid: &ChunkGrid,
+    grid_tform: &GlobalTransform,
+    origin: Vec3,
+    direction: Vec3,
+) -> Option<CubeInd> {
+    // Work in the grid's space, where the plane is y = 0
+    let to_grid = grid_tform.affine().inverse();
+    let origin = to_grid.transform_point3(origin);
+    let direction = to_grid.transform_vector3(direction);
+    if direction.y.abs() < f32::EPSILON {
+        return None;
+    }
+    let dist = -origin.y / direction.y;
+    if dist < 0. {
+        return None;
+    }
+    grid.cell_at(origin + direction * dist)
+}
+
+/// Toggles edit mode, and while it's on, aims at the cell under the
+/// crosshair and draws the highlight box around it.
+#[allow(clippy::type_complexity)]
+pub fn aim_editor(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut games: Query<(&mut Editor, &ChunkGrid, &GlobalTransform)>,
+    camera: Query<&GlobalTransform, With<CameraRotation>>,
+    mut highlight: Query<(&mut Transform, &mut Visibility), With<Highlight>>,
+    mut crosshair: Query<&mut Visibility, (With<Crosshair>, Without<Highlight>)>,
+) {
+    let (Ok((mut editor, grid, grid_tform)), Ok(cam)) =
+        (games.get_single_mut(), camera.get_single())
+    else {
+        return;
+    };
+    if keys.just_pressed(KeyCode::KeyE) {
+        editor.enabled = !editor.enabled;
+    }
+    let vis = if editor.enabled {
+        Visibility::Visible
+    } else {
+        Visibility::Hidden
+    };
+    for mut crosshair in &mut crosshair {
+        crosshair.set_if_neq(vis);
+    }
+
+    let target = editor
+        .enabled
+        .then(|| pick_cell(grid, grid_tform, cam.translation(), cam.forward()))
+        .flatten();
+    if editor.target != target {
+        editor.target = target;
+    }
+    for (mut tform, mut vis) in &mut highlight {
+        let Some(target) = target else {
+            *vis = Visibility::Hidden;
+            continue;
+        };
+        let local = grid.cell_translation(target.row as i64, target.col as i64);
+        tform.translation = grid_tform.transform_point(local);
+        *vis = Visibility::Visible;
+    }
+}
+
+/// In edit mode, left clicking toggles the cell under the crosshair, and
+/// holding the button paints cells to that same state while dragging.
+/// Holding the right button erases. Clicks stamp patterns instead while the
+/// pattern picker is open, and select cells in selection mode.
+pub fn paint_cells(
+    buttons: Res<ButtonInput<MouseButton>>,
+    mut games: Query<(
+        &mut ConwayGol,
+        &mut Editor,
+        Option<&Stamp>,
+        Option<&Selection>,
+    )>,
+) {
+    let Ok((mut gol, mut editor, stamp, selection)) = games.get_single_mut() else {
+        return;
+    };
+    let held = buttons.pressed(MouseButton::Left) || buttons.pressed(MouseButton::Right);
+    let other_tool = stamp.is_some_and(|stamp| stamp.is_open())
+        || selection.is_some_and(|selection| selection.is_enabled());
+    let (true, true, false, Some(target)) = (editor.enabled, held, other_tool, editor.target)
+    else {
+        if editor.painting.is_some() {
+            editor.painting = None;
+        }
+        return;
+    };
+
+    let (is_alive, from) = if buttons.just_pressed(MouseButton::Left) {
+        (!gol.board().get(target.row, target.col), target)
+    } else if buttons.just_pressed(MouseButton::Right) {
+        (false, target)
+    } else if let Some(painting) = editor.painting {
+        painting
+    } else {
+        return;
+    };
+    // The crosshair can skip cells between frames, so fill in the line
+    for cell in line(from, target) {
+        if gol.board().get(cell.row, cell.col) != is_alive {
+            gol.set(cell.row as i64, cell.col as i64, is_alive);
+        }
+    }
+    editor.painting = Some((is_alive, target));
+}
+
+/// The cells along a straight line from one cell to another, inclusive.
+fn line(from: CubeInd, to: CubeInd) -> impl Iterator<Item = CubeInd> {
+    let d_row = to.row as f32 - from.row as f32;
+    let d_col = to.col as f32 - from.col as f32;
+    let steps = d_row.abs().max(d_col.abs()) as usize;
+    (0..=steps).map(move |step| {
+        let t = if steps == 0 {
+            0.
+        } else {
+            step as f32 / steps as f32
+        };
+        CubeInd {
+            row: (from.row as f32 + d_row * t).round() as usize,
+            col: (from.col as f32 + d_col * t).round() as usize,
+        }
+    })
+}
+
+#[cfg(test)]
+mod edit_tests {
+    use super::{line, pick_cell};
+    use crate::scene::{ChunkGrid, CubeInd};
+    use bevy::prelude::*;
+
+    #[test]
+    fn cells_round_trip() {
+        let grid = ChunkGrid::new(10, 20, 40, 30);
+        for (row, col) in [(10, 20), (24, 33), (39, 59)] {
+            let center = grid.cell_translation(row, col);
+            let near = center + Vec3::new(0.9, 0.5, -0.9);
+            let expected = CubeInd {
+                row: row as usize,
+                col: col as usize,
+            };
+            assert_eq!(grid.cell_at(near), Some(expected));
+        }
+        assert_eq!(grid.cell_at(grid.cell_translation(9, 20)), None);
+        assert_eq!(grid.cell_at(grid.cell_translation(10, 60)), None);
+    }
+
+    #[test]
+    fn rays_hit_the_board_plane() {
+        let grid = ChunkGrid::new(0, 0, 16, 16);
+        let tform = GlobalTransform::from_translation(Vec3::new(5., 0., 0.));
+        let cell = CubeInd { row: 3, col: 12 };
+        let target = tform.transform_point(grid.cell_translation(3, 12));
+
+        // Looking straight down and looking on a slant both find the cell
+        let above = target + Vec3::Y * 10.;
+        assert_eq!(pick_cell(&grid, &tform, above, Vec3::NEG_Y), Some(cell));
+        let slanted = target + Vec3::new(6., 8., -3.);
+        assert_eq!(
+            pick_cell(&grid, &tform, slanted, target - slanted),
+            Some(cell)
+        );
+        // Looking away from the plane, along it, or off the grid finds nothing
+        assert_eq!(pick_cell(&grid, &tform, above, Vec3::Y), None);
+        assert_eq!(pick_cell(&grid, &tform, above, Vec3::X), None);
+        let far = target + Vec3::new(0., 10., 100.);
+        assert_eq!(pick_cell(&grid, &tform, far, Vec3::NEG_Y), None);
+    }
+
+    #[test]
+    fn lines_fill_gaps() {
+        let at = |row, col| CubeInd { row, col };
+        let cells: Vec<_> = line(at(2, 2), at(4, 8)).collect();
+        assert_eq!(cells.len(), 7);
+        assert_eq!((cells[0], cells[6]), (at(2, 2), at(4, 8)));
+        assert!(cells.windows(2).all(|pair| pair[1].col == pair[0].col + 1));
+        assert_eq!(line(at(5, 5), at(5, 5)).collect::<Vec<_>>(), vec![at(5, 5)]);
+    }
+}
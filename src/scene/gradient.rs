@@ -0,0 +1,139 @@
+use anyhow::{bail, Context};
+use bevy::{ecs::component::Component, render::color::Color};
+use std::str::FromStr;
+
+/// Colors live cells by how many generations they've been alive, blending
+/// between stops of (age, color). Cells older than the last stop take its
+/// color.
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct AgeGradient {
+    stops: Vec<(u64, Color)>,
+}
+
+impl Default for AgeGradient {
+    /// Newborn cells are pale yellow and cool off through orange and magenta
+    /// to blue, so oscillator rotors stay warm while still lifes turn blue.
+    fn default() -> Self {
+        AgeGradient {
+            stops: vec![
+                (0, Color::rgb(1.0, 0.95, 0.6)),
+                (2, Color::rgb(1.0, 0.45, 0.15)),
+                (8, Color::rgb(0.75, 0.2, 0.55)),
+                (32, Color::rgb(0.25, 0.45, 0.95)),
+            ],
+        }
+    }
+}
+
+impl AgeGradient {
+    /// Returns a gradient through stops of (age, color).
+    /// Errors if there are no stops or their ages aren't increasing.
+    pub fn new(stops: Vec<(u64, Color)>) -> anyhow::Result<Self> {
+        if stops.is_empty() {
+            bail!("A gradient needs at least one stop");
+        }
+        if let Some(pair) = stops.windows(2).find(|pair| pair[0].0 >= pair[1].0) {
+            bail!(
+                "Gradient stops must have increasing ages, but {} comes before {}",
+                pair[0].0,
+                pair[1].0
+            );
+        }
+        Ok(AgeGradient { stops })
+    }
+
+    /// The age past which every cell has the same color.
+    #[inline]
+    pub fn max_age(&self) -> u64 {
+        self.stops.last().map_or(0, |(age, _)| *age)
+    }
+
+    /// The color of a cell that's been alive for `age` generations.
+    pub fn color(&self, age: u64) -> Color {
+        let next = self.stops.partition_point(|(stop, _)| *stop <= age);
+        let (Some((from_age, from)), Some((to_age, to))) =
+            (self.stops.get(next.wrapping_sub(1)), self.stops.get(next))
+        else {
+            let (_, edge) = self.stops[next.min(self.stops.len() - 1)];
+            return edge;
+        };
+        let t = (age - from_age) as f32 / (to_age - from_age) as f32;
+        let blend = |a: f32, b: f32| a + (b - a) * t;
+        Color::rgb(
+            blend(from.r(), to.r()),
+            blend(from.g(), to.g()),
+            blend(from.b(), to.b()),
+        )
+    }
+}
+
+impl FromStr for AgeGradient {
+    type Err = anyhow::Error;
+
+    /// Parses comma separated stops of age and hex color, like
+    /// "0:fff299,32:4073f2".
+    fn from_str(text: &str) -> anyhow::Result<Self> {
+        let stops = text
+            .split(',')
+            .map(|stop| {
+                let (age, color) = stop
+                    .trim()
+                    .split_once(':')
+                    .with_context(|| format!("Gradient stop {stop:?} must be age:color"))?;
+                let age = age
+                    .trim()
+                    .parse()
+                    .with_context(|| format!("Gradient stop {stop:?} has a bad age"))?;
+                let color = Color::hex(color.trim())
+                    .with_context(|| format!("Gradient stop {stop:?} has a bad color"))?;
+                Ok((age, color))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        AgeGradient::new(stops)
+    }
+}
+
+#[cfg(test)]
+mod gradient_tests {
+    use super::AgeGradient;
+    use bevy::render::color::Color;
+
+    #[test]
+    fn blends_between_stops() -> anyhow::Result<()> {
+        let gradient = AgeGradient::new(vec![
+            (2, Color::rgb(1., 0., 0.)),
+            (6, Color::rgb(0., 0., 1.)),
+        ])?;
+        assert_eq!(gradient.max_age(), 6);
+        assert_eq!(gradient.color(0), Color::rgb(1., 0., 0.));
+        assert_eq!(gradient.color(2), Color::rgb(1., 0., 0.));
+        assert_eq!(gradient.color(3), Color::rgb(0.75, 0., 0.25));
+        assert_eq!(gradient.color(6), Color::rgb(0., 0., 1.));
+        assert_eq!(gradient.color(100), Color::rgb(0., 0., 1.));
+
+        let solid = AgeGradient::new(vec![(0, Color::WHITE)])?;
+        assert_eq!(solid.color(5), Color::WHITE);
+        assert!(AgeGradient::new(Vec::new()).is_err());
+        assert!(AgeGradient::new(vec![(3, Color::WHITE), (3, Color::BLACK)]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn parses_stops() -> anyhow::Result<()> {
+        let gradient: AgeGradient = "0:ff0000, 6:#0000ff".parse()?;
+        assert_eq!(
+            gradient,
+            AgeGradient::new(vec![
+                (0, Color::rgb(1., 0., 0.)),
+                (6, Color::rgb(0., 0., 1.)),
+            ])?
+        );
+        for bad in ["", "0", "x:ff0000", "0:red", "4:ffffff,2:000000"] {
+            assert!(
+                bad.parse::<AgeGradient>().is_err(),
+                "{bad:?} should not parse"
+            );
+        }
+        Ok(())
+    }
+}
@@ -1,6 +1,6 @@
 use super::{
-    sim::ConwayGol, CameraRotation, ControlMenu, GameTimer, Paused, MOUSE_SENSITIVITY,
-    POSITION_INCR,
+    sim::ConwayGol, soup::Soup, BoardSettled, CameraRotation, ControlMenu, Editor, GameTimer,
+    Paused, SettleAction, SoupLabel, MOUSE_SENSITIVITY, POSITION_INCR,
 };
 use bevy::{input::mouse::MouseMotion, prelude::*, window::PrimaryWindow};
 use std::time::Duration;
@@ -9,6 +9,14 @@ use std::time::Duration;
 pub fn display_controls(mut commands: Commands) {
     let bindings = r"
 - left click: pause/play
+- e: edit mode: click or drag to paint cells, right click to erase
+- p: pick a pattern to stamp from the library
+- b: select mode: drag to select cells, then ctrl+c/ctrl+x/ctrl+v
+  to copy/cut/paste, n to fill randomly, i to invert, delete to clear
+- ctrl+z: undo an edit
+- ctrl+shift+z: redo an edit
+- f5: save the session
+- f9: load the saved session
 - h: hide/show this menu
 
 - w: forward
@@ -19,6 +27,10 @@ pub fn display_controls(mut commands: Commands) {
 - shift: down
 - up arrow: tick speed 2x
 - down arrow: tick speed 0.5x
+- left arrow: step back one generation while paused
+- right arrow: step forward one generation while paused
+- r: continue, pause, or reseed once the board settles
+- v: show/hide past generations stacked below the board
 - escape: exit
 ";
 
@@ -43,6 +55,58 @@ pub fn display_controls(mut commands: Commands) {
         }),
         ControlMenu,
     ));
+
+    let style = TextStyle {
+        font_size: 18.,
+        color: Color::WHITE,
+        ..default()
+    };
+    commands.spawn((
+        // The soup, the settle action, and how the board settled
+        TextBundle::from_sections([
+            TextSection::new("", style.clone()),
+            TextSection::new("", style.clone()),
+            TextSection::new("", style),
+        ])
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(18.),
+            left: Val::Px(18.),
+            ..default()
+        }),
+        SoupLabel,
+    ));
+}
+
+/// Shows the seed and settings of the soup the board started from, so a
+/// run can be shared and reproduced, along with how the board settled.
+pub fn display_soup(
+    soups: Query<(Ref<Soup>, Option<Ref<SettleAction>>)>,
+    mut settled_events: EventReader<BoardSettled>,
+    mut label: Query<&mut Text, With<SoupLabel>>,
+) {
+    let (Ok((soup, action)), Ok(mut text)) = (soups.get_single(), label.get_single_mut()) else {
+        return;
+    };
+    if soup.is_changed() {
+        text.sections[0].value = format!("soup: {}", *soup);
+        text.sections[2].value.clear();
+    }
+    if let Some(action) = action.filter(|action| action.is_changed()) {
+        text.sections[1].value = format!("\non settle: {:?}", *action).to_lowercase();
+    }
+    if let Some(BoardSettled(settled)) = settled_events.read().last() {
+        text.sections[2].value = format!("\nboard {settled}");
+    }
+}
+
+/// Cycles through what happens once the board settles.
+pub fn cycle_settle_action(keys: Res<ButtonInput<KeyCode>>, mut actions: Query<&mut SettleAction>) {
+    if keys.just_pressed(KeyCode::KeyR) {
+        for mut action in &mut actions {
+            *action = action.next();
+        }
+    }
 }
 
 /// Responds to keyboard input. Handles camera translation.
@@ -51,7 +115,7 @@ pub fn keyboard_motion(
     mut camera: Query<(&mut Transform, &CameraRotation)>,
     windows: Query<&Window>,
     mut ctrl_menu: Query<(&mut Visibility, &ControlMenu)>,
-    mut game_timer: Query<(&ConwayGol, &mut GameTimer)>,
+    mut game_timer: Query<&mut GameTimer>,
 ) {
     if !windows.iter().any(|window| window.focused) {
         return;
@@ -95,7 +159,7 @@ pub fn keyboard_motion(
             };
         }
         if keys.just_pressed(KeyCode::ArrowUp) {
-            let (_, mut timer) = game_timer.single_mut();
+            let mut timer = game_timer.single_mut();
             let new_duration = timer.0.duration() / 2;
             if new_duration < Duration::from_millis(125) {
                 return;
@@ -103,13 +167,33 @@ pub fn keyboard_motion(
             timer.0.set_duration(new_duration);
         }
         if keys.just_pressed(KeyCode::ArrowDown) {
-            let (_, mut timer) = game_timer.single_mut();
+            let mut timer = game_timer.single_mut();
             let new_duration = timer.0.duration() * 2;
             timer.0.set_duration(new_duration);
         }
     }
 }
 
+/// Steps the simulation backward or forward one generation at a time
+/// while it's paused.
+pub fn step_generations(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut game_state: Query<(&mut ConwayGol, &Paused)>,
+) {
+    let Ok((mut gol, sim)) = game_state.get_single_mut() else {
+        return;
+    };
+    if !sim.is_paused() {
+        return;
+    }
+    if keys.just_pressed(KeyCode::ArrowLeft) {
+        gol.step_back();
+    }
+    if keys.just_pressed(KeyCode::ArrowRight) {
+        gol.tick();
+    }
+}
+
 /// Rotates the camera in response to mouse movement (Minecraft style).
 pub fn ego_camera(
     mut mouse_motion: EventReader<MouseMotion>,
@@ -138,12 +222,16 @@ pub fn hide_cursor(mut primary_window: Query<&mut Window, With<PrimaryWindow>>)
     primary_window.single_mut().cursor.visible = false;
 }
 
-/// Toggles the simulation's pause state when a user clicks
+/// Toggles the simulation's pause state when a user clicks outside of edit
+/// mode
 pub fn handle_click(
     buttons: Res<ButtonInput<MouseButton>>,
-    mut game_state: Query<(&ConwayGol, &mut Paused)>,
+    mut game_state: Query<(&mut Paused, Option<&Editor>)>,
 ) {
     if buttons.just_pressed(MouseButton::Left) {
-        game_state.single_mut().1.toggle();
+        let (mut paused, editor) = game_state.single_mut();
+        if !editor.is_some_and(|editor| editor.is_enabled()) {
+            paused.toggle();
+        }
     }
 }
@@ -0,0 +1,231 @@
+use super::{bitboard::BitBoard, sim::Bounds};
+use bevy::render::{
+    color::Color,
+    mesh::{Indices, Mesh, PrimitiveTopology},
+    render_asset::RenderAssetUsages,
+};
+
+/// Half the side length of each cell's cube
+const HALF_CUBE: f32 = 1.;
+
+/// The widest gap between neighboring cubes that still hides the faces
+/// between them
+const MAX_CULLED_GAP: f32 = 0.5;
+
+/// A face of a cube, as its outward normal and its corners wound
+/// counterclockwise when seen from outside
+struct Face {
+    normal: [f32; 3],
+    corners: [[f32; 3]; 4],
+    /// Row, col offset of the neighbor the face points toward, if any
+    toward: Option<(isize, isize)>,
+}
+
+/// Rows run toward -x and columns toward -z, so a cube's +x face points at
+/// the row above it and its +z face at the column to its left.
+const FACES: [Face; 6] = [
+    Face {
+        normal: [0., 1., 0.],
+        corners: [[-1., 1., -1.], [-1., 1., 1.], [1., 1., 1.], [1., 1., -1.]],
+        toward: None,
+    },
+    Face {
+        normal: [0., -1., 0.],
+        corners: [
+            [-1., -1., -1.],
+            [1., -1., -1.],
+            [1., -1., 1.],
+            [-1., -1., 1.],
+        ],
+        toward: None,
+    },
+    Face {
+        normal: [1., 0., 0.],
+        corners: [[1., -1., -1.], [1., 1., -1.], [1., 1., 1.], [1., -1., 1.]],
+        toward: Some((-1, 0)),
+    },
+    Face {
+        normal: [-1., 0., 0.],
+        corners: [
+            [-1., -1., -1.],
+            [-1., -1., 1.],
+            [-1., 1., 1.],
+            [-1., 1., -1.],
+        ],
+        toward: Some((1, 0)),
+    },
+    Face {
+        normal: [0., 0., 1.],
+        corners: [[-1., -1., 1.], [1., -1., 1.], [1., 1., 1.], [-1., 1., 1.]],
+        toward: Some((0, -1)),
+    },
+    Face {
+        normal: [0., 0., -1.],
+        corners: [
+            [-1., -1., -1.],
+            [-1., 1., -1.],
+            [1., 1., -1.],
+            [1., -1., -1.],
+        ],
+        toward: Some((0, 1)),
+    },
+];
+
+/// Builds one mesh holding a 2x2x2 cube for every live cell of a board inside
+/// `area`, colored per cell. The cube of cell area.top + r, area.left + c sits
+/// at x = -spacing * r, z = -spacing * c. Side faces that point at a live
+/// cell inside `shown` are left out when the cubes are close enough that
+/// that cell's cube hides all but a sliver of them.
+pub fn cube_mesh(
+    cells: &BitBoard,
+    area: Bounds,
+    shown: Bounds,
+    spacing: f32,
+    color: impl Fn(usize, usize) -> Color,
+) -> Mesh {
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut colors = Vec::new();
+    let mut indices = Vec::new();
+    let culls = spacing - 2. * HALF_CUBE <= MAX_CULLED_GAP;
+    let is_shown_alive = |row: i64, col: i64| {
+        shown.contains(row, col)
+            && row >= 0
+            && col >= 0
+            && (row as usize) < cells.height()
+            && (col as usize) < cells.width()
+            && cells.get(row as usize, col as usize)
+    };
+
+    for row in area.top..=area.bottom {
+        for col in area.left..=area.right {
+            if !is_shown_alive(row, col) {
+                continue;
+            }
+            let x = -spacing * (row - area.top) as f32;
+            let z = -spacing * (col - area.left) as f32;
+            let rgba = color(row as usize, col as usize).as_linear_rgba_f32();
+            for face in &FACES {
+                let hidden = culls
+                    && face.toward.is_some_and(|(d_row, d_col)| {
+                        is_shown_alive(row + d_row as i64, col + d_col as i64)
+                    });
+                if hidden {
+                    continue;
+                }
+                let base = positions.len() as u32;
+                positions.extend(
+                    face.corners.map(|[cx, cy, cz]| {
+                        [x + cx * HALF_CUBE, cy * HALF_CUBE, z + cz * HALF_CUBE]
+                    }),
+                );
+                normals.extend([face.normal; 4]);
+                colors.extend([rgba; 4]);
+                indices.extend([base, base + 1, base + 2, base, base + 2, base + 3]);
+            }
+        }
+    }
+
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, colors)
+    .with_inserted_indices(Indices::U32(indices))
+}
+
+/// An empty mesh for chunks to hold until their cells are first drawn.
+pub fn empty_mesh() -> Mesh {
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, Vec::<[f32; 3]>::new())
+    .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, Vec::<[f32; 3]>::new())
+    .with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, Vec::<[f32; 4]>::new())
+    .with_inserted_indices(Indices::U32(Vec::new()))
+}
+
+#[cfg(test)]
+mod mesh_tests {
+    use super::cube_mesh;
+    use crate::scene::{bitboard::BitBoard, config::DEFAULT_CUBE_SPACING, sim::Bounds};
+    use bevy::render::{
+        color::Color,
+        mesh::{Mesh, VertexAttributeValues},
+    };
+
+    fn bounds(top: i64, left: i64, bottom: i64, right: i64) -> Bounds {
+        Bounds {
+            top,
+            left,
+            bottom,
+            right,
+        }
+    }
+
+    fn positions(mesh: &Mesh) -> &[[f32; 3]] {
+        match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+            Some(VertexAttributeValues::Float32x3(values)) => values,
+            _ => panic!("Meshes should have positions"),
+        }
+    }
+
+    #[test]
+    fn culls_shared_faces() {
+        let mut cells = BitBoard::new(6, 4);
+        // A lone cube and a domino
+        cells.set(0, 0, true);
+        cells.set(2, 3, true);
+        cells.set(2, 4, true);
+        let all = bounds(0, 0, 3, 5);
+        let mesh = cube_mesh(&cells, all, all, DEFAULT_CUBE_SPACING, |_, _| Color::WHITE);
+        // Six faces for the lone cube, and five for each half of the domino
+        assert_eq!(mesh.count_vertices(), (6 + 5 + 5) * 4);
+        assert_eq!(mesh.indices().map(|ind| ind.len()), Some((6 + 5 + 5) * 6));
+
+        // Each half of the domino is whole when the other isn't shown
+        let left = bounds(0, 0, 3, 3);
+        assert_eq!(
+            cube_mesh(&cells, left, left, DEFAULT_CUBE_SPACING, |_, _| {
+                Color::WHITE
+            })
+            .count_vertices(),
+            12 * 4
+        );
+        // Only the area is drawn, but neighbors anywhere shown hide faces
+        let domino_left = bounds(2, 3, 2, 3);
+        let mesh = cube_mesh(&cells, domino_left, all, DEFAULT_CUBE_SPACING, |_, _| {
+            Color::WHITE
+        });
+        assert_eq!(mesh.count_vertices(), 5 * 4);
+
+        // Nothing is culled once the gaps between cubes are wide
+        let mesh = cube_mesh(&cells, all, all, 4., |_, _| Color::WHITE);
+        assert_eq!(mesh.count_vertices(), 3 * 6 * 4);
+    }
+
+    #[test]
+    fn positions_and_colors() {
+        let mut cells = BitBoard::new(8, 8);
+        cells.set(5, 6, true);
+        let area = bounds(4, 4, 7, 7);
+        let mesh = cube_mesh(&cells, area, area, DEFAULT_CUBE_SPACING, |row, col| {
+            Color::rgb(row as f32 / 10., col as f32 / 10., 0.)
+        });
+        // Row 5, col 6 is one row and two cols into the area
+        let (x, z) = (-DEFAULT_CUBE_SPACING, -2. * DEFAULT_CUBE_SPACING);
+        assert!(positions(&mesh)
+            .iter()
+            .all(|[px, py, pz]| (px - x).abs() == 1. && py.abs() == 1. && (pz - z).abs() == 1.));
+
+        let Some(VertexAttributeValues::Float32x4(colors)) = mesh.attribute(Mesh::ATTRIBUTE_COLOR)
+        else {
+            panic!("Meshes should have colors");
+        };
+        let expected = Color::rgb(0.5, 0.6, 0.).as_linear_rgba_f32();
+        assert!(colors.iter().all(|rgba| *rgba == expected));
+    }
+}
@@ -1,20 +1,37 @@
 use bevy::{
-    ecs::component::Component,
+    ecs::{component::Component, entity::Entity, event::Event},
+    math::Vec3,
     time::{Timer, TimerMode},
 };
+use sim::{Bounds, Settled};
 use std::time::Duration;
 
+pub mod bitboard;
+pub mod census;
+pub mod config;
+pub mod edit;
+pub mod gradient;
 pub mod interaction;
+pub mod mesh;
+pub mod pattern;
+pub mod rule;
+pub mod select;
+pub mod session;
 pub mod sim;
+pub mod soup;
+pub mod spacetime;
+pub mod sparse;
+pub mod stamp;
+pub mod topology;
+pub mod undo;
 pub mod world;
 
 /// Units moved per event trigger
 const MOUSE_SENSITIVITY: f32 = 0.2;
 const POSITION_INCR: f32 = 0.25;
 
-/// Configures the size of the grid of cubes
-const BOARD_SIZE: usize = 2usize.pow(7);
-const CUBE_SPACING: f32 = 2.25;
+/// Cells along each side of a chunk of the grid that's drawn as one mesh
+const CHUNK_SIZE: usize = 32;
 
 /// Whether or not the cube simulation is paused.
 #[derive(Component)]
@@ -44,17 +61,195 @@ pub struct GameTimer(pub Timer);
 
 impl Default for GameTimer {
     fn default() -> Self {
-        GameTimer(Timer::new(Duration::from_millis(500), TimerMode::Repeating))
+        GameTimer::new(config::DEFAULT_TICK)
     }
 }
 
-/// Associates a cube with its row, col pair in the underlying simulation grid
-#[derive(Component, Default)]
+impl GameTimer {
+    /// Returns a timer that ticks the game every `interval`.
+    pub fn new(interval: Duration) -> Self {
+        GameTimer(Timer::new(interval, TimerMode::Repeating))
+    }
+}
+
+/// A row, col pair in the underlying simulation grid, such as the cell
+/// under the crosshair
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub struct CubeInd {
     row: usize,
     col: usize,
 }
 
+/// The chunk meshes showing a rectangle of the simulation grid. Each chunk
+/// draws up to CHUNK_SIZE x CHUNK_SIZE cells as one mesh, so a tick only
+/// rebuilds the chunks whose cells changed.
+#[derive(Component)]
+pub struct ChunkGrid {
+    /// Chunk entities in row-major order
+    chunks: Vec<Entity>,
+    top: usize,
+    left: usize,
+    width: usize,
+    height: usize,
+    /// Distance between neighboring cubes. Rows run along the world's x
+    /// axis and columns along its z axis.
+    spacing: f32,
+}
+
+impl ChunkGrid {
+    /// Returns a grid showing the width x height cells whose top left cell
+    /// is at top, left, before its chunks are spawned.
+    pub fn new(top: usize, left: usize, width: usize, height: usize) -> Self {
+        ChunkGrid {
+            chunks: Vec::new(),
+            top,
+            left,
+            width,
+            height,
+            spacing: config::DEFAULT_CUBE_SPACING,
+        }
+    }
+
+    /// Spaces neighboring cubes `spacing` apart instead.
+    pub fn with_spacing(mut self, spacing: f32) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    /// Distance between neighboring cubes.
+    #[inline]
+    pub fn spacing(&self) -> f32 {
+        self.spacing
+    }
+
+    /// Chunk entities in row-major order.
+    #[inline]
+    pub fn chunks(&self) -> &[Entity] {
+        &self.chunks
+    }
+
+    /// Number of chunks across the grid.
+    #[inline]
+    pub fn chunk_cols(&self) -> usize {
+        self.width.div_ceil(CHUNK_SIZE)
+    }
+
+    /// Number of chunks in the grid if all of them were spawned.
+    pub fn chunk_count(&self) -> usize {
+        self.chunk_cols() * self.height.div_ceil(CHUNK_SIZE)
+    }
+
+    /// Every cell in view.
+    pub fn view(&self) -> Bounds {
+        Bounds {
+            top: self.top as i64,
+            left: self.left as i64,
+            bottom: (self.top + self.height) as i64 - 1,
+            right: (self.left + self.width) as i64 - 1,
+        }
+    }
+
+    /// The cells chunk `ind` shows.
+    pub fn chunk_bounds(&self, ind: usize) -> Bounds {
+        let top = self.top + ind / self.chunk_cols() * CHUNK_SIZE;
+        let left = self.left + ind % self.chunk_cols() * CHUNK_SIZE;
+        let bottom = (top + CHUNK_SIZE).min(self.top + self.height) - 1;
+        let right = (left + CHUNK_SIZE).min(self.left + self.width) - 1;
+        Bounds {
+            top: top as i64,
+            left: left as i64,
+            bottom: bottom as i64,
+            right: right as i64,
+        }
+    }
+
+    /// Index of the chunk showing the cell at row, col, if that cell is in view.
+    pub fn chunk_of(&self, row: usize, col: usize) -> Option<usize> {
+        let (row, col) = (row.checked_sub(self.top)?, col.checked_sub(self.left)?);
+        if row >= self.height || col >= self.width {
+            return None;
+        }
+        Some(row / CHUNK_SIZE * self.chunk_cols() + col / CHUNK_SIZE)
+    }
+
+    /// The cell whose cube is centered nearest a point relative to the
+    /// grid's entity, if that cell is in view. Undoes `cell_translation`.
+    pub fn cell_at(&self, point: Vec3) -> Option<CubeInd> {
+        let (middle_row, middle_col) = (self.height as f32 / 2., self.width as f32 / 2.);
+        let row = (middle_row - point.x / self.spacing).round();
+        let col = (middle_col - point.z / self.spacing).round();
+        if row < 0. || col < 0. || row >= self.height as f32 || col >= self.width as f32 {
+            return None;
+        }
+        Some(CubeInd {
+            row: self.top + row as usize,
+            col: self.left + col as usize,
+        })
+    }
+
+    /// Where the cube of the cell at row, col sits relative to the grid's
+    /// entity. The middle of the view is at the origin, with rows running
+    /// toward -x and columns toward -z.
+    pub fn cell_translation(&self, row: i64, col: i64) -> Vec3 {
+        let (middle_row, middle_col) = (self.height as f32 / 2., self.width as f32 / 2.);
+        Vec3::new(
+            self.spacing * (middle_row - (row - self.top as i64) as f32),
+            0.,
+            self.spacing * (middle_col - (col - self.left as i64) as f32),
+        )
+    }
+}
+
+/// Lets the player edit the cells under the crosshair, instead of pausing
+/// and playing on click
+#[derive(Component, Debug, Default)]
+pub struct Editor {
+    enabled: bool,
+    /// The cell under the crosshair
+    target: Option<CubeInd>,
+    /// While a mouse button is held, the state cells are painted to and the
+    /// cell painted last
+    painting: Option<(bool, CubeInd)>,
+}
+
+impl Editor {
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+}
+
 /// Marker struct for help menu text
 #[derive(Component)]
 pub struct ControlMenu;
+
+/// Marker struct for the text describing the soup the board started from
+/// and how the board has settled
+#[derive(Component)]
+pub struct SoupLabel;
+
+/// What the simulation does once its board dies out or starts repeating
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SettleAction {
+    /// Keep ticking
+    #[default]
+    Continue,
+    Pause,
+    /// Start over from a new soup with the same settings
+    Reseed,
+}
+
+impl SettleAction {
+    /// The action after this one, wrapping around.
+    pub fn next(self) -> Self {
+        match self {
+            SettleAction::Continue => SettleAction::Pause,
+            SettleAction::Pause => SettleAction::Reseed,
+            SettleAction::Reseed => SettleAction::Continue,
+        }
+    }
+}
+
+/// Sent when the simulation's board dies out or starts repeating
+#[derive(Event, Debug, Clone, Copy)]
+pub struct BoardSettled(pub Settled);
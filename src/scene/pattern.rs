@@ -0,0 +1,272 @@
+use super::{
+    bitboard::BitBoard,
+    rule::Rule,
+    sim::{Bounds, ConwayGol, HashLife, Universe},
+};
+use anyhow::{bail, Context};
+use std::{fs, path::Path};
+
+pub mod library;
+pub mod life;
+pub mod macrocell;
+pub mod plaintext;
+pub mod rle;
+
+/// A pattern of live cells along with the metadata pattern files carry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    pub name: Option<String>,
+    pub author: Option<String>,
+    pub comments: Vec<String>,
+    /// The rule the pattern is meant to run under, if the file names one
+    pub rule: Option<Rule>,
+    pub cells: BitBoard,
+}
+
+impl Pattern {
+    /// Returns a pattern with no metadata.
+    pub fn new(cells: BitBoard) -> Self {
+        Pattern {
+            name: None,
+            author: None,
+            comments: Vec::new(),
+            rule: None,
+            cells,
+        }
+    }
+
+    /// Returns a pattern holding the live cells of a board, cropped to
+    /// their bounding box.
+    pub fn from_board(board: &BitBoard) -> Self {
+        let bounds = Bounds::enclosing(
+            board
+                .iter_alive()
+                .map(|(row, col)| (row as i64, col as i64)),
+        );
+        let cells = match bounds {
+            Some(b) => board.crop(b.top as usize, b.left as usize, b.width(), b.height()),
+            None => BitBoard::new(0, 0),
+        };
+        Pattern::new(cells)
+    }
+
+    /// Returns a pattern holding a set of live cells, shifted so that their
+    /// bounding box starts at row 0, col 0.
+    pub fn from_cells(cells: impl IntoIterator<Item = (i64, i64)>) -> Self {
+        let cells: Vec<_> = cells.into_iter().collect();
+        let Some(bounds) = Bounds::enclosing(cells.iter().copied()) else {
+            return Pattern::new(BitBoard::new(0, 0));
+        };
+        let mut board = BitBoard::new(bounds.width(), bounds.height());
+        for (row, col) in cells {
+            board.set(
+                (row - bounds.top) as usize,
+                (col - bounds.left) as usize,
+                true,
+            );
+        }
+        Pattern::new(board)
+    }
+
+    /// Returns a pattern holding the live cells and rule of a simulation.
+    pub fn from_gol(gol: &ConwayGol) -> Self {
+        Pattern {
+            rule: Some(gol.rule()),
+            ..Pattern::from_board(gol.board())
+        }
+    }
+
+    /// Returns a pattern holding the live cells and rule of a HashLife
+    /// universe, cropped to their bounding box.
+    pub fn from_hashlife(hl: &HashLife) -> Self {
+        let cells = match hl.bounds() {
+            Some(b) => hl.window(b.top, b.left, b.width(), b.height()),
+            None => BitBoard::new(0, 0),
+        };
+        Pattern {
+            rule: Some(hl.rule()),
+            ..Pattern::new(cells)
+        }
+    }
+}
+
+/// The pattern file formats that can be read and written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatternFormat {
+    Rle,
+    Plaintext,
+    Life105,
+    Life106,
+    Macrocell,
+}
+
+impl PatternFormat {
+    /// Guesses a pattern's format from the text of its file.
+    /// Errors if the text doesn't look like any supported format.
+    pub fn detect(text: &str) -> anyhow::Result<Self> {
+        let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+        let Some(first) = lines.clone().next() else {
+            bail!("Pattern file is empty");
+        };
+        if first.starts_with(life::LIFE_105_HEADER) {
+            return Ok(PatternFormat::Life105);
+        }
+        if first.starts_with(life::LIFE_106_HEADER) {
+            return Ok(PatternFormat::Life106);
+        }
+        if first.starts_with(macrocell::MACROCELL_HEADER) {
+            return Ok(PatternFormat::Macrocell);
+        }
+        if first.starts_with('!') {
+            return Ok(PatternFormat::Plaintext);
+        }
+        // RLE files open with comments and then an "x = ..." header
+        if let Some(header) = lines.find(|line| !line.starts_with('#')) {
+            if header.starts_with('x') && header.contains('=') {
+                return Ok(PatternFormat::Rle);
+            }
+            if header.chars().all(|ch| matches!(ch, '.' | 'O' | '*')) {
+                return Ok(PatternFormat::Plaintext);
+            }
+        }
+        bail!("Unrecognized pattern format")
+    }
+
+    /// Picks a format from a file extension.
+    pub fn from_path(path: &Path) -> anyhow::Result<Self> {
+        let ext = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default();
+        Ok(match ext.to_ascii_lowercase().as_str() {
+            "rle" => PatternFormat::Rle,
+            "cells" => PatternFormat::Plaintext,
+            "lif" | "life" => PatternFormat::Life106,
+            "mc" => PatternFormat::Macrocell,
+            _ => bail!("No pattern format uses the extension {ext:?}"),
+        })
+    }
+
+    /// Parses pattern text in this format.
+    pub fn read(&self, text: &str) -> anyhow::Result<Pattern> {
+        match self {
+            PatternFormat::Rle => rle::read_rle(text),
+            PatternFormat::Plaintext => plaintext::read_cells(text),
+            PatternFormat::Life105 => life::read_life105(text),
+            PatternFormat::Life106 => life::read_life106(text),
+            PatternFormat::Macrocell => {
+                Ok(Pattern::from_hashlife(&macrocell::read_macrocell(text)?))
+            }
+        }
+    }
+
+    /// Writes a pattern as text in this format.
+    /// Errors if the format can't record the pattern's rule, as Macrocell
+    /// can't for rules with B0.
+    pub fn write(&self, pattern: &Pattern) -> anyhow::Result<String> {
+        let text = match self {
+            PatternFormat::Rle => rle::write_rle(pattern),
+            PatternFormat::Plaintext => plaintext::write_cells(pattern),
+            PatternFormat::Life105 => life::write_life105(pattern),
+            PatternFormat::Life106 => life::write_life106(pattern),
+            PatternFormat::Macrocell => {
+                let hl = HashLife::from_board(&pattern.cells, 0, 0)
+                    .with_rule(pattern.rule.unwrap_or_default())
+                    .context("Macrocell patterns are run by HashLife")?;
+                macrocell::write_macrocell(&hl)
+            }
+        };
+        Ok(text)
+    }
+}
+
+/// Parses pattern text in any supported format.
+pub fn parse_pattern(text: &str) -> anyhow::Result<Pattern> {
+    PatternFormat::detect(text)?.read(text)
+}
+
+/// Reads a pattern file in any supported format, detected from its contents.
+pub fn load_pattern(path: impl AsRef<Path>) -> anyhow::Result<Pattern> {
+    let path = path.as_ref();
+    let text = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read pattern file {}", path.display()))?;
+    parse_pattern(&text).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// Reads a pattern file into a HashLife universe with its top left cell at
+/// the origin. Macrocell files are read straight into the tree, so they can
+/// be far larger than any board.
+pub fn load_hashlife(path: impl AsRef<Path>) -> anyhow::Result<HashLife> {
+    let path = path.as_ref();
+    let text = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read pattern file {}", path.display()))?;
+    let parse = || {
+        if PatternFormat::detect(&text)? == PatternFormat::Macrocell {
+            return macrocell::read_macrocell(&text);
+        }
+        let pattern = parse_pattern(&text)?;
+        HashLife::from_board(&pattern.cells, 0, 0).with_rule(pattern.rule.unwrap_or_default())
+    };
+    parse().with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// Writes a pattern file in the format matching its extension.
+pub fn save_pattern(path: impl AsRef<Path>, pattern: &Pattern) -> anyhow::Result<()> {
+    let path = path.as_ref();
+    let text = PatternFormat::from_path(path)?.write(pattern)?;
+    fs::write(path, text).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod pattern_tests {
+    use super::{load_pattern, parse_pattern, save_pattern, Pattern, PatternFormat};
+    use std::env;
+
+    #[test]
+    fn detects_formats() -> anyhow::Result<()> {
+        let cases = [
+            ("#N Block\nx = 2, y = 2\n2o$2o!", PatternFormat::Rle),
+            ("x=2,y=2\n2o$2o!", PatternFormat::Rle),
+            ("!Name: Block\nOO\nOO", PatternFormat::Plaintext),
+            ("OO\nOO", PatternFormat::Plaintext),
+            ("#Life 1.05\n**\n**", PatternFormat::Life105),
+            ("#Life 1.06\n0 0\n0 1\n1 0\n1 1", PatternFormat::Life106),
+            (
+                "[M2] (golly 2.0)\n#R B3/S23\n**$**$\n",
+                PatternFormat::Macrocell,
+            ),
+        ];
+        for (text, format) in cases {
+            assert_eq!(PatternFormat::detect(text)?, format, "{text:?}");
+            assert_eq!(parse_pattern(text)?.cells.population(), 4, "{text:?}");
+        }
+        assert!(PatternFormat::detect("").is_err());
+        assert!(PatternFormat::detect("hello").is_err());
+        Ok(())
+    }
+
+    /// Every format saved to disk loads back to the same cells
+    #[test]
+    fn save_and_load() -> anyhow::Result<()> {
+        let glider = parse_pattern("#N Glider\nx = 3, y = 3\nbo$2bo$3o!")?;
+        let dir = env::temp_dir();
+        for ext in ["rle", "cells", "lif", "mc"] {
+            let path = dir.join(format!("conway_pattern_test.{ext}"));
+            save_pattern(&path, &glider)?;
+            let loaded = load_pattern(&path)?;
+            std::fs::remove_file(&path)?;
+            assert_eq!(loaded.cells, glider.cells, "{ext}");
+        }
+        assert!(save_pattern(dir.join("glider.txt"), &glider).is_err());
+
+        // Macrocell can't record B0 rules, which HashLife can't run
+        let b0 = Pattern {
+            rule: Some("B03/S23".parse()?),
+            ..glider
+        };
+        assert!(PatternFormat::Macrocell.write(&b0).is_err());
+        assert!(PatternFormat::Rle.write(&b0)?.contains("B03/S23"));
+        assert!(load_pattern(dir.join("conway_missing_pattern.rle")).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,191 @@
+use super::{rle::read_rle, Pattern};
+use anyhow::Context;
+use std::fmt;
+
+/// The kinds of objects in the pattern library
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    StillLife,
+    Oscillator,
+    Spaceship,
+    Gun,
+    Puffer,
+    Methuselah,
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Category::StillLife => "still life",
+            Category::Oscillator => "oscillator",
+            Category::Spaceship => "spaceship",
+            Category::Gun => "gun",
+            Category::Puffer => "puffer",
+            Category::Methuselah => "methuselah",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// A well-known pattern built into the game as RLE text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LibraryEntry {
+    pub name: &'static str,
+    pub category: Category,
+    rle: &'static str,
+}
+
+impl LibraryEntry {
+    /// Parses the entry's cells and metadata.
+    pub fn pattern(&self) -> anyhow::Result<Pattern> {
+        read_rle(self.rle).with_context(|| format!("Library pattern {} is malformed", self.name))
+    }
+}
+
+/// Every built-in pattern, grouped by category
+pub const LIBRARY: &[LibraryEntry] = &[
+    LibraryEntry {
+        name: "Block",
+        category: Category::StillLife,
+        rle: include_str!("library/block.rle"),
+    },
+    LibraryEntry {
+        name: "Beehive",
+        category: Category::StillLife,
+        rle: include_str!("library/beehive.rle"),
+    },
+    LibraryEntry {
+        name: "Loaf",
+        category: Category::StillLife,
+        rle: include_str!("library/loaf.rle"),
+    },
+    LibraryEntry {
+        name: "Boat",
+        category: Category::StillLife,
+        rle: include_str!("library/boat.rle"),
+    },
+    LibraryEntry {
+        name: "Blinker",
+        category: Category::Oscillator,
+        rle: include_str!("library/blinker.rle"),
+    },
+    LibraryEntry {
+        name: "Toad",
+        category: Category::Oscillator,
+        rle: include_str!("library/toad.rle"),
+    },
+    LibraryEntry {
+        name: "Beacon",
+        category: Category::Oscillator,
+        rle: include_str!("library/beacon.rle"),
+    },
+    LibraryEntry {
+        name: "Pulsar",
+        category: Category::Oscillator,
+        rle: include_str!("library/pulsar.rle"),
+    },
+    LibraryEntry {
+        name: "Pentadecathlon",
+        category: Category::Oscillator,
+        rle: include_str!("library/pentadecathlon.rle"),
+    },
+    LibraryEntry {
+        name: "Glider",
+        category: Category::Spaceship,
+        rle: include_str!("library/glider.rle"),
+    },
+    LibraryEntry {
+        name: "Lightweight spaceship",
+        category: Category::Spaceship,
+        rle: include_str!("library/lwss.rle"),
+    },
+    LibraryEntry {
+        name: "Middleweight spaceship",
+        category: Category::Spaceship,
+        rle: include_str!("library/mwss.rle"),
+    },
+    LibraryEntry {
+        name: "Heavyweight spaceship",
+        category: Category::Spaceship,
+        rle: include_str!("library/hwss.rle"),
+    },
+    LibraryEntry {
+        name: "Gosper glider gun",
+        category: Category::Gun,
+        rle: include_str!("library/gosper_glider_gun.rle"),
+    },
+    LibraryEntry {
+        name: "Puffer train",
+        category: Category::Puffer,
+        rle: include_str!("library/puffer_train.rle"),
+    },
+    LibraryEntry {
+        name: "R-pentomino",
+        category: Category::Methuselah,
+        rle: include_str!("library/r_pentomino.rle"),
+    },
+    LibraryEntry {
+        name: "Diehard",
+        category: Category::Methuselah,
+        rle: include_str!("library/diehard.rle"),
+    },
+    LibraryEntry {
+        name: "Acorn",
+        category: Category::Methuselah,
+        rle: include_str!("library/acorn.rle"),
+    },
+];
+
+#[cfg(test)]
+mod library_tests {
+    use super::{Category, LIBRARY};
+    use crate::scene::{
+        census::classify::{classify, Classification},
+        rule::Rule,
+        sim::Universe,
+        sparse::SparseGol,
+    };
+
+    /// Every entry parses, is named the same in its file, and behaves like
+    /// its category says when run alone
+    #[test]
+    fn entries_match_their_categories() -> anyhow::Result<()> {
+        for entry in LIBRARY {
+            let pattern = entry.pattern()?;
+            assert_eq!(pattern.name.as_deref(), Some(entry.name));
+            let cells: Vec<_> = pattern
+                .cells
+                .iter_alive()
+                .map(|(row, col)| (row as i64, col as i64))
+                .collect();
+            let found = classify(&cells, Rule::CONWAY, 100)?;
+            let matches = match entry.category {
+                Category::StillLife => matches!(found, Classification::StillLife { .. }),
+                Category::Oscillator => matches!(found, Classification::Oscillator { .. }),
+                Category::Spaceship => matches!(found, Classification::Spaceship { .. }),
+                Category::Methuselah => found == Classification::Unsettled,
+                Category::Gun | Category::Puffer => {
+                    // Both leave ever more behind them
+                    let mut universe = SparseGol::new();
+                    for (row, col) in &cells {
+                        universe.set(*row, *col, true);
+                    }
+                    let mut populations = Vec::new();
+                    for _ in 0..3 {
+                        for _ in 0..120 {
+                            universe.tick();
+                        }
+                        populations.push(universe.population());
+                    }
+                    populations.windows(2).all(|pair| pair[0] < pair[1])
+                }
+            };
+            assert!(
+                matches,
+                "{} is a {} but ran as {found:?}",
+                entry.name, entry.category
+            );
+        }
+        Ok(())
+    }
+}
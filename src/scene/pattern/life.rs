@@ -0,0 +1,192 @@
+use super::Pattern;
+use crate::scene::rule::Rule;
+use anyhow::{bail, Context};
+
+/// First line of a Life 1.05 file
+pub const LIFE_105_HEADER: &str = "#Life 1.05";
+
+/// First line of a Life 1.06 file
+pub const LIFE_106_HEADER: &str = "#Life 1.06";
+
+/// Parses a pattern in the Life 1.05 format, which lists blocks of cells
+/// drawn with '.' and '*', each positioned by a "#P x y" line:
+/// https://conwaylife.com/wiki/Life_1.05
+pub fn read_life105(text: &str) -> anyhow::Result<Pattern> {
+    let mut lines = text.lines().map(str::trim);
+    if lines.next() != Some(LIFE_105_HEADER) {
+        bail!("Life 1.05 files must start with {LIFE_105_HEADER:?}");
+    }
+
+    let mut comments = Vec::new();
+    let mut rule = None;
+    let mut alive = Vec::new();
+    let (mut block_row, mut block_col) = (0i64, 0i64);
+    let mut row = 0i64;
+    for line in lines.filter(|line| !line.is_empty()) {
+        if let Some(description) = line.strip_prefix("#D") {
+            comments.push(description.trim().to_string());
+        } else if line == "#N" {
+            rule = Some(Rule::CONWAY);
+        } else if let Some(rulestring) = line.strip_prefix("#R") {
+            rule = Some(rulestring.trim().parse()?);
+        } else if let Some(position) = line.strip_prefix("#P") {
+            let coords = parse_coords(position)?;
+            (block_col, block_row) = coords;
+            row = 0;
+        } else if line.starts_with('#') {
+            continue;
+        } else {
+            for (col, ch) in line.chars().enumerate() {
+                match ch {
+                    '.' => {}
+                    '*' => alive.push((block_row + row, block_col + col as i64)),
+                    _ => bail!("Unexpected '{ch}' in Life 1.05 row {line:?}"),
+                }
+            }
+            row += 1;
+        }
+    }
+
+    let mut pattern = Pattern::from_cells(alive);
+    pattern.comments = comments;
+    pattern.rule = rule;
+    Ok(pattern)
+}
+
+/// Writes a pattern in the Life 1.05 format as a single block centered
+/// on the origin. Metadata is written as description lines.
+pub fn write_life105(pattern: &Pattern) -> String {
+    let mut out = format!("{LIFE_105_HEADER}\n");
+    let descriptions = pattern
+        .name
+        .iter()
+        .chain(&pattern.author)
+        .chain(&pattern.comments);
+    for description in descriptions {
+        out.push_str(&format!("#D {description}\n"));
+    }
+    match pattern.rule {
+        None | Some(Rule::CONWAY) => out.push_str("#N\n"),
+        Some(rule) => {
+            // Life 1.05 spells rules survival first, like "23/3"
+            let counts = |pred: &dyn Fn(usize) -> bool| {
+                (0..=8)
+                    .filter(|ct| pred(*ct))
+                    .map(|ct| ct.to_string())
+                    .collect::<String>()
+            };
+            let survival = counts(&|ct| rule.survives(ct));
+            let birth = counts(&|ct| rule.births(ct));
+            out.push_str(&format!("#R {survival}/{birth}\n"));
+        }
+    }
+    let cells = &pattern.cells;
+    out.push_str(&format!(
+        "#P {} {}\n",
+        -(cells.width() as i64 / 2),
+        -(cells.height() as i64 / 2)
+    ));
+    for row in cells.to_rows() {
+        let len = row
+            .iter()
+            .rposition(|is_alive| *is_alive)
+            .map_or(0, |ind| ind + 1);
+        if len == 0 {
+            out.push('.');
+        }
+        out.extend(
+            row[..len]
+                .iter()
+                .map(|is_alive| if *is_alive { '*' } else { '.' }),
+        );
+        out.push('\n');
+    }
+    out
+}
+
+/// Parses a pattern in the Life 1.06 format, which lists the "x y"
+/// coordinates of every live cell: https://conwaylife.com/wiki/Life_1.06
+pub fn read_life106(text: &str) -> anyhow::Result<Pattern> {
+    let mut lines = text.lines().map(str::trim);
+    if lines.next() != Some(LIFE_106_HEADER) {
+        bail!("Life 1.06 files must start with {LIFE_106_HEADER:?}");
+    }
+    let mut alive = Vec::new();
+    for line in lines.filter(|line| !line.is_empty() && !line.starts_with('#')) {
+        let (col, row) = parse_coords(line)?;
+        alive.push((row, col));
+    }
+    Ok(Pattern::from_cells(alive))
+}
+
+/// Writes a pattern in the Life 1.06 format with the pattern's top left
+/// corner at the origin. Metadata can't be stored and is dropped.
+pub fn write_life106(pattern: &Pattern) -> String {
+    let mut out = format!("{LIFE_106_HEADER}\n");
+    for (row, col) in pattern.cells.iter_alive() {
+        out.push_str(&format!("{col} {row}\n"));
+    }
+    out
+}
+
+/// Parses a whitespace separated "x y" pair.
+fn parse_coords(text: &str) -> anyhow::Result<(i64, i64)> {
+    let mut parts = text.split_whitespace().map(|part| {
+        part.parse::<i64>()
+            .with_context(|| format!("Invalid coordinate {part:?} in {text:?}"))
+    });
+    let (Some(x), Some(y), None) = (parts.next(), parts.next(), parts.next()) else {
+        bail!("Expected an \"x y\" coordinate pair, found {text:?}");
+    };
+    Ok((x?, y?))
+}
+
+#[cfg(test)]
+mod life_tests {
+    use super::{read_life105, read_life106, write_life105, write_life106};
+    use crate::scene::rule::Rule;
+
+    #[test]
+    fn life105_blocks() -> anyhow::Result<()> {
+        let text = "#Life 1.05\n#D Two gliders\n#R 23/36\n#P -1 -1\n.*.\n..*\n***\n#P 10 4\n*\n";
+        let pattern = read_life105(text)?;
+        assert_eq!(pattern.comments, vec!["Two gliders"]);
+        assert_eq!(pattern.rule, Some("B36/S23".parse()?));
+        assert_eq!((pattern.cells.width(), pattern.cells.height()), (12, 6));
+        assert_eq!(pattern.cells.population(), 6);
+        assert!(pattern.cells.get(0, 1));
+        assert!(pattern.cells.get(5, 11));
+
+        let reread = read_life105(&write_life105(&pattern))?;
+        assert_eq!(reread, pattern);
+        Ok(())
+    }
+
+    #[test]
+    fn life105_normal_rule() -> anyhow::Result<()> {
+        let pattern = read_life105("#Life 1.05\n#N\n**\n**\n")?;
+        assert_eq!(pattern.rule, Some(Rule::CONWAY));
+        assert_eq!(
+            write_life105(&pattern),
+            "#Life 1.05\n#N\n#P -1 -1\n**\n**\n"
+        );
+        assert!(read_life105("#Life 1.06\n0 0\n").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn life106_round_trip() -> anyhow::Result<()> {
+        let text = "#Life 1.06\n0 -1\n1 0\n-1 1\n0 1\n1 1\n";
+        let glider = read_life106(text)?;
+        assert_eq!(glider.cells.population(), 5);
+        assert!(glider.cells.get(0, 1) && glider.cells.get(2, 0));
+        assert_eq!(
+            write_life106(&glider),
+            "#Life 1.06\n1 0\n2 1\n0 2\n1 2\n2 2\n"
+        );
+        assert_eq!(read_life106(&write_life106(&glider))?, glider);
+        assert!(read_life106("#Life 1.06\n0\n").is_err());
+        assert!(read_life106("#Life 1.06\n0 1 2\n").is_err());
+        Ok(())
+    }
+}
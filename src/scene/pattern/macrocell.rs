@@ -0,0 +1,300 @@
+use crate::scene::{
+    rule::Rule,
+    sim::{hashlife::NodeId, HashLife},
+};
+use anyhow::{bail, Context};
+use std::collections::HashMap;
+
+/// First token of a Macrocell file
+pub const MACROCELL_HEADER: &str = "[M2]";
+
+/// Width of the leaf squares two-state files draw with '.' and '*'
+const LEAF_WIDTH: usize = 8;
+
+/// Level of the leaf squares two-state files draw with '.' and '*'
+const LEAF_LEVEL: u8 = 3;
+
+/// Parses a Macrocell file into a HashLife universe:
+/// https://conwaylife.com/wiki/Macrocell
+/// Each line after the header defines a quadtree node numbered from one, either
+/// as an 8x8 square drawn with '.', '*' and '$', or as "level nw ne sw se" where
+/// the children are earlier node numbers and zero is an empty node. Level one
+/// nodes in multi-state files list cell states instead, and every non-zero
+/// state is read as alive. The last node is the root, centered on the origin.
+pub fn read_macrocell(text: &str) -> anyhow::Result<HashLife> {
+    let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+    if !lines
+        .next()
+        .is_some_and(|line| line.starts_with(MACROCELL_HEADER))
+    {
+        bail!("Macrocell files must start with {MACROCELL_HEADER:?}");
+    }
+
+    let mut rule = Rule::default();
+    let mut generation = 0;
+    let mut node_lines = Vec::new();
+    for line in lines {
+        if let Some(rulestring) = line.strip_prefix("#R") {
+            rule = rulestring
+                .trim()
+                .parse()
+                .context("Macrocell rule is not a supported Life-like rule")?;
+        } else if let Some(gen) = line.strip_prefix("#G") {
+            generation = gen.trim().parse().context("Invalid Macrocell generation")?;
+        } else if !line.starts_with('#') {
+            node_lines.push(line);
+        }
+    }
+
+    let mut hl = HashLife::new().with_rule(rule)?.with_generation(generation);
+    // Index zero stands in for the empty node of whatever level is needed
+    let mut nodes: Vec<NodeId> = vec![0];
+    for (ind, line) in node_lines.iter().enumerate() {
+        let node = if line.starts_with(['.', '*', '$']) {
+            read_leaf_square(&mut hl, line)?
+        } else {
+            read_node_line(&mut hl, line, &nodes)
+                .with_context(|| format!("Invalid Macrocell node {} {line:?}", ind + 1))?
+        };
+        nodes.push(node);
+    }
+
+    let Some(&root) = nodes.get(1..).and_then(|nodes| nodes.last()) else {
+        bail!("Macrocell file has no nodes");
+    };
+    let half = 1i64 << (hl.node_level(root) - 1);
+    hl.set_root(root, (-half, -half))?;
+    Ok(hl)
+}
+
+/// Writes a HashLife universe as a two-state Macrocell file. The root is
+/// centered on the origin, so the pattern may come back translated.
+pub fn write_macrocell(hl: &HashLife) -> String {
+    let mut out = format!("{MACROCELL_HEADER} (conway)\n#R {}\n", hl.rule());
+    if hl.generation() > 0 {
+        out.push_str(&format!("#G {}\n", hl.generation()));
+    }
+    let mut numbers = HashMap::new();
+    write_node(hl, hl.root(), &mut numbers, &mut out);
+    out
+}
+
+/// Builds a level three node from a line like "$$..*$...*$.***$".
+fn read_leaf_square(hl: &mut HashLife, line: &str) -> anyhow::Result<NodeId> {
+    let mut cells = [[false; LEAF_WIDTH]; LEAF_WIDTH];
+    let (mut row, mut col) = (0, 0);
+    for ch in line.chars() {
+        match ch {
+            '$' => {
+                row += 1;
+                col = 0;
+                continue;
+            }
+            '.' | '*' if row < LEAF_WIDTH && col < LEAF_WIDTH => cells[row][col] = ch == '*',
+            '.' | '*' => bail!("Macrocell leaf {line:?} is larger than 8x8"),
+            _ => bail!("Unexpected '{ch}' in Macrocell leaf {line:?}"),
+        }
+        col += 1;
+    }
+    build_square(hl, &cells, 0, 0, LEAF_WIDTH)
+}
+
+/// Builds the node for a size x size square of cells whose top left cell
+/// is at top, left.
+fn build_square(
+    hl: &mut HashLife,
+    cells: &[[bool; LEAF_WIDTH]; LEAF_WIDTH],
+    top: usize,
+    left: usize,
+    size: usize,
+) -> anyhow::Result<NodeId> {
+    if size == 1 {
+        return Ok(HashLife::leaf(cells[top][left]));
+    }
+    let half = size / 2;
+    let nw = build_square(hl, cells, top, left, half)?;
+    let ne = build_square(hl, cells, top, left + half, half)?;
+    let sw = build_square(hl, cells, top + half, left, half)?;
+    let se = build_square(hl, cells, top + half, left + half, half)?;
+    hl.join(nw, ne, sw, se)
+}
+
+/// Builds a node from a line like "5 1 0 0 2".
+fn read_node_line(hl: &mut HashLife, line: &str, nodes: &[NodeId]) -> anyhow::Result<NodeId> {
+    let fields = line
+        .split_whitespace()
+        .map(|field| field.parse::<usize>().context("Expected a number"))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let [level, nw, ne, sw, se] = fields[..] else {
+        bail!("Expected a level and four children");
+    };
+    let level = u8::try_from(level).context("Level is too deep")?;
+    if level == 0 {
+        bail!("Level must be at least one");
+    }
+
+    let mut children = [0; 4];
+    for (child, number) in children.iter_mut().zip([nw, ne, sw, se]) {
+        *child = if level == 1 {
+            // Multi-state files give the states of the four cells directly
+            HashLife::leaf(number != 0)
+        } else if number == 0 {
+            hl.empty(level - 1)?
+        } else {
+            let Some(&node) = nodes.get(number) else {
+                bail!("Node {number} hasn't been defined yet");
+            };
+            if hl.node_level(node) != level - 1 {
+                bail!("Node {number} is not at level {}", level - 1);
+            }
+            node
+        };
+    }
+    hl.join(children[0], children[1], children[2], children[3])
+}
+
+/// Writes a node after its children, returning its line number or zero
+/// if it's empty. Nodes that were already written are reused.
+fn write_node(
+    hl: &HashLife,
+    node: NodeId,
+    numbers: &mut HashMap<NodeId, usize>,
+    out: &mut String,
+) -> usize {
+    if hl.node_population(node) == 0 {
+        return 0;
+    }
+    if let Some(&number) = numbers.get(&node) {
+        return number;
+    }
+
+    let level = hl.node_level(node);
+    if level == LEAF_LEVEL {
+        for row in 0..LEAF_WIDTH {
+            let line: String = (0..LEAF_WIDTH)
+                .map(|col| {
+                    if cell_in(hl, node, row, col) {
+                        '*'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            out.push_str(line.trim_end_matches('.'));
+            out.push('$');
+        }
+    } else {
+        let children = hl
+            .children(node)
+            .map(|child| write_node(hl, child, numbers, out));
+        out.push_str(&format!(
+            "{level} {} {} {} {}",
+            children[0], children[1], children[2], children[3]
+        ));
+    }
+    out.push('\n');
+    let number = numbers.len() + 1;
+    numbers.insert(node, number);
+    number
+}
+
+/// Whether the cell at row, col relative to a node's top left corner is alive.
+fn cell_in(hl: &HashLife, mut node: NodeId, mut row: usize, mut col: usize) -> bool {
+    for level in (1..=hl.node_level(node)).rev() {
+        let half = 1 << (level - 1);
+        let quad = (row >= half) as usize * 2 + (col >= half) as usize;
+        node = hl.children(node)[quad];
+        row %= half;
+        col %= half;
+    }
+    node == HashLife::leaf(true)
+}
+
+#[cfg(test)]
+mod macrocell_tests {
+    use super::{read_macrocell, write_macrocell};
+    use crate::scene::{
+        pattern::rle::{read_rle, rle_tests::GOSPER_GLIDER_GUN},
+        sim::{Bounds, HashLife, Universe},
+    };
+
+    #[test]
+    fn reads_two_state_leaves() -> anyhow::Result<()> {
+        let text = "[M2] (golly 2.0)\n#R B3/S23\n$$..*$...*$.***$$$$\n4 1 0 0 1\n";
+        let mut hl = read_macrocell(text)?;
+        assert_eq!(hl.population(), 10);
+        // The level 4 root spans -8..8, and each glider starts two cells in
+        assert!(hl.is_alive(-6, -6) && hl.is_alive(-4, -7));
+        assert!(hl.is_alive(2, 2) && hl.is_alive(4, 1));
+
+        for _ in 0..4 {
+            hl.tick();
+        }
+        assert_eq!(
+            hl.bounds(),
+            Some(Bounds {
+                top: -5,
+                left: -6,
+                bottom: 5,
+                right: 4
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn reads_multi_state_nodes() -> anyhow::Result<()> {
+        let text = "[M2]\n#R B3/S23\n1 0 1 0 1\n1 2 1 0 0\n2 1 2 0 0\n3 3 0 0 3\n";
+        let hl = read_macrocell(text)?;
+        assert_eq!(hl.population(), 8);
+        for (row, col) in [
+            (-4, -3),
+            (-3, -3),
+            (-4, -2),
+            (-4, -1),
+            (0, 1),
+            (1, 1),
+            (0, 3),
+        ] {
+            assert!(hl.is_alive(row, col), "{row}, {col}");
+        }
+        assert!(!hl.is_alive(-4, -4));
+        Ok(())
+    }
+
+    /// A glider gun written to Macrocell reads back identically and keeps firing
+    #[test]
+    fn gosper_glider_gun_round_trip() -> anyhow::Result<()> {
+        let gun = read_rle(GOSPER_GLIDER_GUN)?;
+        let mut hl = HashLife::from_board(&gun.cells, 0, 0);
+        hl.advance(90)?;
+        let written = write_macrocell(&hl);
+        assert!(written.starts_with("[M2] (conway)\n#R B3/S23\n#G 90\n"));
+
+        let mut reread = read_macrocell(&written)?;
+        assert_eq!(reread.generation(), 90);
+        assert_eq!(reread.population(), hl.population());
+        let (a, b) = (hl.bounds().unwrap(), reread.bounds().unwrap());
+        assert_eq!((a.width(), a.height()), (b.width(), b.height()));
+        assert_eq!(
+            hl.window(a.top, a.left, a.width(), a.height()),
+            reread.window(b.top, b.left, b.width(), b.height())
+        );
+        assert_eq!(write_macrocell(&reread), written);
+
+        reread.advance(30)?;
+        assert_eq!(reread.population(), hl.population() + 5);
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_malformed() {
+        assert!(read_macrocell("").is_err());
+        assert!(read_macrocell("x = 1, y = 1\no!").is_err());
+        assert!(read_macrocell("[M2]\n").is_err());
+        assert!(read_macrocell("[M2]\n4 1 0 0 0\n").is_err());
+        assert!(read_macrocell("[M2]\n1 0 0 0\n").is_err());
+        assert!(read_macrocell("[M2]\n.*a$\n").is_err());
+        assert!(read_macrocell("[M2]\n1 0 1 0 1\n3 1 0 0 0\n").is_err());
+    }
+}
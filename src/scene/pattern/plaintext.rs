@@ -0,0 +1,105 @@
+use super::Pattern;
+use crate::scene::bitboard::BitBoard;
+use anyhow::bail;
+
+/// Parses a pattern in the plaintext .cells format:
+/// https://conwaylife.com/wiki/Plaintext
+/// Rows may leave out their trailing dead cells.
+pub fn read_cells(text: &str) -> anyhow::Result<Pattern> {
+    let mut pattern = Pattern::new(BitBoard::new(0, 0));
+    let mut rows: Vec<Vec<bool>> = Vec::new();
+    for line in text.lines().map(str::trim_end) {
+        if let Some(comment) = line.strip_prefix('!') {
+            if let Some(name) = comment.strip_prefix("Name:") {
+                pattern.name = Some(name.trim().to_string());
+            } else if let Some(author) = comment.strip_prefix("Author:") {
+                pattern.author = Some(author.trim().to_string());
+            } else {
+                pattern.comments.push(comment.trim().to_string());
+            }
+            continue;
+        }
+        let mut row = Vec::with_capacity(line.len());
+        for ch in line.chars() {
+            row.push(match ch {
+                '.' => false,
+                'O' | '*' => true,
+                _ => bail!("Unexpected '{ch}' in plaintext pattern row {line:?}"),
+            });
+        }
+        rows.push(row);
+    }
+    while rows.last().is_some_and(|row| row.is_empty()) {
+        rows.pop();
+    }
+
+    let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    let mut cells = BitBoard::new(width, rows.len());
+    for (row_ind, row) in rows.iter().enumerate() {
+        for (col, is_alive) in row.iter().enumerate() {
+            if *is_alive {
+                cells.set(row_ind, col, true);
+            }
+        }
+    }
+    pattern.cells = cells;
+    Ok(pattern)
+}
+
+/// Writes a pattern in the plaintext .cells format with trailing dead
+/// cells left off of each row.
+pub fn write_cells(pattern: &Pattern) -> String {
+    let mut out = String::new();
+    if let Some(name) = &pattern.name {
+        out.push_str(&format!("!Name: {name}\n"));
+    }
+    if let Some(author) = &pattern.author {
+        out.push_str(&format!("!Author: {author}\n"));
+    }
+    for comment in &pattern.comments {
+        out.push_str(&format!("!{comment}\n"));
+    }
+    for row in pattern.cells.to_rows() {
+        let len = row
+            .iter()
+            .rposition(|is_alive| *is_alive)
+            .map_or(0, |ind| ind + 1);
+        out.extend(
+            row[..len]
+                .iter()
+                .map(|is_alive| if *is_alive { 'O' } else { '.' }),
+        );
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod plaintext_tests {
+    use super::{read_cells, write_cells};
+
+    const GLIDER: &str =
+        "!Name: Glider\n!Author: Richard K. Guy\n!The smallest spaceship\n.O\n..O\nOOO\n";
+
+    #[test]
+    fn round_trip() -> anyhow::Result<()> {
+        let glider = read_cells(GLIDER)?;
+        assert_eq!(glider.name.as_deref(), Some("Glider"));
+        assert_eq!(glider.author.as_deref(), Some("Richard K. Guy"));
+        assert_eq!(glider.comments, vec!["The smallest spaceship"]);
+        assert_eq!((glider.cells.width(), glider.cells.height()), (3, 3));
+        assert_eq!(glider.cells.population(), 5);
+        assert_eq!(write_cells(&glider), GLIDER);
+        Ok(())
+    }
+
+    #[test]
+    fn blank_rows() -> anyhow::Result<()> {
+        let blocks = read_cells("OO\nOO\n\n\n*\n\n")?;
+        assert_eq!(blocks.cells.height(), 5);
+        assert!(blocks.cells.get(4, 0));
+        assert_eq!(write_cells(&blocks), "OO\nOO\n\n\nO\n");
+        assert!(read_cells("OO\nOx\n").is_err());
+        Ok(())
+    }
+}
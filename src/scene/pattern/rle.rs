@@ -0,0 +1,252 @@
+use super::Pattern;
+use crate::scene::bitboard::BitBoard;
+use anyhow::{bail, Context};
+
+/// Longest line `write_rle` produces, as recommended by the format
+const MAX_LINE_LEN: usize = 70;
+
+/// Parses a pattern in Run Length Encoded format:
+/// https://conwaylife.com/wiki/Run_Length_Encoded
+/// Multi-state files are read as two-state, with every non-zero state alive.
+pub fn read_rle(text: &str) -> anyhow::Result<Pattern> {
+    let mut pattern = Pattern::new(BitBoard::new(0, 0));
+    let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+
+    let header = loop {
+        let Some(line) = lines.next() else {
+            bail!("RLE file has no header line");
+        };
+        let Some(comment) = line.strip_prefix('#') else {
+            break line;
+        };
+        let mut chars = comment.chars();
+        let tag = chars.next();
+        let body = chars.as_str().trim();
+        match tag {
+            Some('N') => pattern.name = Some(body.to_string()),
+            Some('O') => pattern.author = Some(body.to_string()),
+            Some('C' | 'c') => pattern.comments.push(body.to_string()),
+            Some('r') => pattern.rule = Some(body.parse()?),
+            // Position hints and unknown tags don't affect the cells
+            _ => {}
+        }
+    };
+
+    let mut width = None;
+    let mut height = None;
+    for field in header.split(',') {
+        let Some((key, value)) = field.split_once('=') else {
+            bail!("Malformed RLE header field {field:?}");
+        };
+        let value = value.trim();
+        match key.trim() {
+            "x" => width = Some(value.parse::<usize>().context("Invalid RLE width")?),
+            "y" => height = Some(value.parse::<usize>().context("Invalid RLE height")?),
+            "rule" => pattern.rule = Some(value.parse()?),
+            _ => {}
+        }
+    }
+    let (Some(width), Some(height)) = (width, height) else {
+        bail!("RLE header {header:?} must give both x and y");
+    };
+
+    let mut cells = BitBoard::new(width, height);
+    let (mut row, mut col) = (0, 0);
+    let mut run: Option<usize> = None;
+    'parse: for line in lines {
+        for ch in line.chars() {
+            let len = run.unwrap_or(1);
+            match ch {
+                '0'..='9' => {
+                    let digit = ch as usize - '0' as usize;
+                    run = Some(run.unwrap_or(0) * 10 + digit);
+                    continue;
+                }
+                '!' => break 'parse,
+                '$' => {
+                    row += len;
+                    col = 0;
+                }
+                'b' | '.' => col += len,
+                'o' | 'A'..='Z' => {
+                    if row >= height || col + len > width {
+                        bail!("RLE cells run past the {width}x{height} size in the header");
+                    }
+                    for offset in 0..len {
+                        cells.set(row, col + offset, true);
+                    }
+                    col += len;
+                }
+                ch if ch.is_whitespace() => continue,
+                _ => bail!("Unexpected '{ch}' in RLE pattern data"),
+            }
+            run = None;
+        }
+    }
+
+    pattern.cells = cells;
+    Ok(pattern)
+}
+
+/// Writes a pattern in Run Length Encoded format. Rows drop their trailing
+/// dead cells, and lines wrap before they exceed 70 characters.
+pub fn write_rle(pattern: &Pattern) -> String {
+    let mut out = String::new();
+    if let Some(name) = &pattern.name {
+        out.push_str(&format!("#N {name}\n"));
+    }
+    if let Some(author) = &pattern.author {
+        out.push_str(&format!("#O {author}\n"));
+    }
+    for comment in &pattern.comments {
+        out.push_str(&format!("#C {comment}\n"));
+    }
+    let cells = &pattern.cells;
+    out.push_str(&format!(
+        "x = {}, y = {}, rule = {}\n",
+        cells.width(),
+        cells.height(),
+        pattern.rule.unwrap_or_default()
+    ));
+
+    let mut tokens = Vec::new();
+    let mut pending_rows = 0;
+    for row in 0..cells.height() {
+        let mut col = 0;
+        while col < cells.width() {
+            let is_alive = cells.get(row, col);
+            let len = (col..cells.width())
+                .take_while(|c| cells.get(row, *c) == is_alive)
+                .count();
+            col += len;
+            if !is_alive && col == cells.width() {
+                // Trailing dead cells are implied
+                break;
+            }
+            if pending_rows > 0 {
+                tokens.push(run_token(pending_rows, '$'));
+                pending_rows = 0;
+            }
+            tokens.push(run_token(len, if is_alive { 'o' } else { 'b' }));
+        }
+        pending_rows += 1;
+    }
+    tokens.push("!".to_string());
+
+    let mut line_len = 0;
+    for token in tokens {
+        if line_len + token.len() > MAX_LINE_LEN {
+            out.push('\n');
+            line_len = 0;
+        }
+        line_len += token.len();
+        out.push_str(&token);
+    }
+    out.push('\n');
+    out
+}
+
+/// Formats one run, leaving out the count when it's one.
+fn run_token(len: usize, tag: char) -> String {
+    if len == 1 {
+        tag.to_string()
+    } else {
+        format!("{len}{tag}")
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod rle_tests {
+    use super::{read_rle, write_rle};
+    use crate::scene::{bitboard::BitBoard, pattern::Pattern, rule::Rule, sim::ConwayGol};
+
+    pub const GOSPER_GLIDER_GUN: &str = "\
+#N Gosper glider gun
+#O Bill Gosper
+#C A true period 30 glider gun.
+#C The first known gun and the first known finite pattern with unbounded growth.
+x = 36, y = 9, rule = B3/S23
+24bo11b$22bobo11b$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o14b$2o8b
+o3bob2o4bobo11b$10bo5bo7bo11b$11bo3bo20b$12b2o22b!
+";
+
+    #[test]
+    fn reads_gosper_glider_gun() -> anyhow::Result<()> {
+        let gun = read_rle(GOSPER_GLIDER_GUN)?;
+        assert_eq!(gun.name.as_deref(), Some("Gosper glider gun"));
+        assert_eq!(gun.author.as_deref(), Some("Bill Gosper"));
+        assert_eq!(gun.comments.len(), 2);
+        assert_eq!(gun.rule, Some(Rule::CONWAY));
+        assert_eq!((gun.cells.width(), gun.cells.height()), (36, 9));
+        assert_eq!(gun.cells.population(), 36);
+        assert!(gun.cells.get(0, 24));
+        assert!(gun.cells.get(4, 0) && gun.cells.get(4, 1));
+        Ok(())
+    }
+
+    #[test]
+    fn gosper_glider_gun_round_trip() -> anyhow::Result<()> {
+        let gun = read_rle(GOSPER_GLIDER_GUN)?;
+        let written = write_rle(&gun);
+        assert_eq!(
+            written,
+            "\
+#N Gosper glider gun
+#O Bill Gosper
+#C A true period 30 glider gun.
+#C The first known gun and the first known finite pattern with unbounded growth.
+x = 36, y = 9, rule = B3/S23
+24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$2o8bo3bob2o4b
+obo$10bo5bo7bo$11bo3bo$12b2o!
+"
+        );
+        assert_eq!(read_rle(&written)?, gun);
+        Ok(())
+    }
+
+    /// The gun fires a glider every 30 generations once it's placed on a board
+    #[test]
+    fn gun_fires_at_offset() -> anyhow::Result<()> {
+        let gun = read_rle(GOSPER_GLIDER_GUN)?;
+        let mut gol = ConwayGol::from_board(BitBoard::new(64, 64));
+        gol.place(&gun.cells, 5, 7)?;
+        assert!(gol.board().get(5, 31));
+        for _ in 0..60 {
+            gol.tick();
+        }
+        assert_eq!(gol.board().population(), 36 + 2 * 5);
+
+        let exported = Pattern::from_gol(&gol);
+        let reread = read_rle(&write_rle(&exported))?;
+        assert_eq!(reread.cells, exported.cells);
+        assert!(gol.place(&gun.cells, 40, 40).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn blank_rows_and_multistate() -> anyhow::Result<()> {
+        let pattern = read_rle("#C two blocks\nx = 2, y = 5\n2o$2o3$2A!")?;
+        assert_eq!(pattern.rule, None);
+        assert_eq!(pattern.cells.population(), 6);
+        assert!(pattern.cells.get(4, 1));
+        assert!(!pattern.cells.get(3, 0));
+
+        let mut with_blank_rows = pattern.clone();
+        with_blank_rows.comments.clear();
+        assert_eq!(
+            write_rle(&with_blank_rows),
+            "x = 2, y = 5, rule = B3/S23\n2o$2o3$2o!\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_malformed() {
+        assert!(read_rle("").is_err());
+        assert!(read_rle("#N no header").is_err());
+        assert!(read_rle("x = 2\n2o!").is_err());
+        assert!(read_rle("x = 2, y = 1\n3o!").is_err());
+        assert!(read_rle("x = 2, y = 1\n2q!").is_err());
+        assert!(read_rle("x = 2, y = 1, rule = B9/S\n2o!").is_err());
+    }
+}
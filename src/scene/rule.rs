@@ -0,0 +1,210 @@
+use anyhow::{bail, Context};
+use std::{fmt, str::FromStr};
+
+/// An outer-totalistic Life-like rule in B/S notation. Bit n of `birth` is set
+/// if a dead cell with n live neighbors is born, and bit n of `survival` is set
+/// if a live cell with n live neighbors survives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rule {
+    birth: u16,
+    survival: u16,
+}
+
+impl Rule {
+    /// Conway's Game of Life, B3/S23
+    pub const CONWAY: Rule = Rule {
+        birth: 1 << 3,
+        survival: (1 << 2) | (1 << 3),
+    };
+
+    /// Builds a rule from lists of neighbor counts.
+    /// Errors if any count exceeds eight.
+    pub fn new(birth: &[u8], survival: &[u8]) -> anyhow::Result<Self> {
+        let mut rule = Rule {
+            birth: 0,
+            survival: 0,
+        };
+        for &ct in birth {
+            if ct > 8 {
+                bail!("Birth count {ct} is out of range 0..=8");
+            }
+            rule.birth |= 1 << ct;
+        }
+        for &ct in survival {
+            if ct > 8 {
+                bail!("Survival count {ct} is out of range 0..=8");
+            }
+            rule.survival |= 1 << ct;
+        }
+        Ok(rule)
+    }
+
+    /// Whether a dead cell with `nb_ct` live neighbors comes alive.
+    #[inline]
+    pub fn births(&self, nb_ct: usize) -> bool {
+        nb_ct <= 8 && self.birth & (1 << nb_ct) != 0
+    }
+
+    /// Whether a live cell with `nb_ct` live neighbors stays alive.
+    #[inline]
+    pub fn survives(&self, nb_ct: usize) -> bool {
+        nb_ct <= 8 && self.survival & (1 << nb_ct) != 0
+    }
+
+    /// Returns the next state of a cell given its current state and
+    /// live neighbor count.
+    #[inline]
+    pub fn next_state(&self, is_alive: bool, nb_ct: usize) -> bool {
+        if is_alive {
+            self.survives(nb_ct)
+        } else {
+            self.births(nb_ct)
+        }
+    }
+
+    /// Parses the digits of one half of a rulestring into a neighbor count mask.
+    fn parse_counts(digits: &str, rulestring: &str) -> anyhow::Result<u16> {
+        let mut mask = 0u16;
+        for ch in digits.chars() {
+            let ct = ch
+                .to_digit(10)
+                .with_context(|| format!("Unexpected '{ch}' in rulestring {rulestring:?}"))?;
+            if ct > 8 {
+                bail!("Neighbor count {ct} in rulestring {rulestring:?} is out of range 0..=8");
+            }
+            if mask & (1 << ct) != 0 {
+                bail!("Neighbor count {ct} is repeated in rulestring {rulestring:?}");
+            }
+            mask |= 1 << ct;
+        }
+        Ok(mask)
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Rule::CONWAY
+    }
+}
+
+impl FromStr for Rule {
+    type Err = anyhow::Error;
+
+    /// Parses "B3/S23" style rulestrings (case insensitive, either half may
+    /// come first, slash optional) and the legacy survival/birth "23/3" form.
+    fn from_str(rulestring: &str) -> anyhow::Result<Self> {
+        let trimmed = rulestring.trim();
+        if trimmed.is_empty() {
+            bail!("Rulestring is empty");
+        }
+
+        let lower = trimmed.to_ascii_lowercase();
+        if !lower.contains(['b', 's']) {
+            // Legacy notation lists survival counts, then birth counts
+            let Some((survival, birth)) = lower.split_once('/') else {
+                bail!("Rulestring {rulestring:?} must be in B/S or S/B notation");
+            };
+            return Ok(Rule {
+                birth: Self::parse_counts(birth, rulestring)?,
+                survival: Self::parse_counts(survival, rulestring)?,
+            });
+        }
+
+        let mut birth = None;
+        let mut survival = None;
+        let halves: Vec<&str> = if lower.contains('/') {
+            lower.split('/').collect()
+        } else {
+            // "B3S23" has no separator, so split before the second letter
+            match lower[1..].find(['b', 's']) {
+                Some(ind) => vec![&lower[..=ind], &lower[(ind + 1)..]],
+                None => vec![&lower[..]],
+            }
+        };
+        if halves.len() > 2 {
+            bail!("Rulestring {rulestring:?} has too many '/' separators");
+        }
+        for half in halves {
+            let (slot, digits) = if let Some(digits) = half.strip_prefix('b') {
+                (&mut birth, digits)
+            } else if let Some(digits) = half.strip_prefix('s') {
+                (&mut survival, digits)
+            } else {
+                bail!("Expected 'B' or 'S' at the start of {half:?} in rulestring {rulestring:?}");
+            };
+            if slot.is_some() {
+                bail!("Rulestring {rulestring:?} specifies the same half twice");
+            }
+            *slot = Some(Self::parse_counts(digits, rulestring)?);
+        }
+
+        let (Some(birth), Some(survival)) = (birth, survival) else {
+            bail!("Rulestring {rulestring:?} must include both a B and an S section");
+        };
+        Ok(Rule { birth, survival })
+    }
+}
+
+impl fmt::Display for Rule {
+    /// Writes the rule in canonical "B3/S23" form.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "B")?;
+        for ct in (0..=8).filter(|ct| self.births(*ct)) {
+            write!(f, "{ct}")?;
+        }
+        write!(f, "/S")?;
+        for ct in (0..=8).filter(|ct| self.survives(*ct)) {
+            write!(f, "{ct}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod rule_tests {
+    use super::Rule;
+
+    #[test]
+    fn parses_bs_notation() -> anyhow::Result<()> {
+        assert_eq!("B3/S23".parse::<Rule>()?, Rule::CONWAY);
+        assert_eq!("b3/s23".parse::<Rule>()?, Rule::CONWAY);
+        assert_eq!("S23/B3".parse::<Rule>()?, Rule::CONWAY);
+        assert_eq!("B3S23".parse::<Rule>()?, Rule::CONWAY);
+        assert_eq!("B36/S23".parse::<Rule>()?, Rule::new(&[3, 6], &[2, 3])?);
+        assert_eq!("B2/S".parse::<Rule>()?, Rule::new(&[2], &[])?);
+        Ok(())
+    }
+
+    #[test]
+    fn parses_legacy_notation() -> anyhow::Result<()> {
+        assert_eq!("23/3".parse::<Rule>()?, Rule::CONWAY);
+        assert_eq!("23/36".parse::<Rule>()?, "B36/S23".parse()?);
+        assert_eq!("/2".parse::<Rule>()?, "B2/S".parse()?);
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_malformed() {
+        for bad in ["", "B3", "B9/S23", "B3/S2a", "B33/S23", "B3/B3", "B3/S2/S3", "X3/S23", "233"] {
+            assert!(bad.parse::<Rule>().is_err(), "{bad:?} should not parse");
+        }
+    }
+
+    #[test]
+    fn display_round_trip() -> anyhow::Result<()> {
+        for rs in ["B3/S23", "B36/S23", "B2/S", "B/S012345678"] {
+            assert_eq!(rs.parse::<Rule>()?.to_string(), rs);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn next_state() {
+        let rule = Rule::CONWAY;
+        assert!(rule.next_state(false, 3));
+        assert!(!rule.next_state(false, 2));
+        assert!(rule.next_state(true, 2));
+        assert!(!rule.next_state(true, 4));
+        assert!(!rule.next_state(true, 1));
+    }
+}
@@ -0,0 +1,198 @@
+use super::{
+    bitboard::BitBoard,
+    sim::{Bounds, ConwayGol},
+    stamp::Stamp,
+    ChunkGrid, CubeInd, Editor,
+};
+use bevy::prelude::*;
+use rand::thread_rng;
+
+/// Chance each cell is alive when a selection is filled at random
+const FILL_DENSITY: f64 = 0.5;
+
+/// A rectangle of cells dragged out on the board for region edits, along
+/// with the cells last copied or cut
+#[derive(Component, Debug, Default)]
+pub struct Selection {
+    enabled: bool,
+    /// The cell the current drag started from
+    anchor: Option<CubeInd>,
+    area: Option<Bounds>,
+    clipboard: Option<BitBoard>,
+}
+
+impl Selection {
+    /// Whether selection mode is on, in which case dragging selects cells
+    /// instead of painting them.
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    #[inline]
+    pub fn area(&self) -> Option<Bounds> {
+        self.area
+    }
+
+    #[inline]
+    pub fn clipboard(&self) -> Option<&BitBoard> {
+        self.clipboard.as_ref()
+    }
+}
+
+/// Marker struct for the box drawn around the selected cells
+#[derive(Component)]
+pub struct SelectionBox;
+
+/// Spawns the selection's box, hidden until cells are selected.
+pub fn setup_selection(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(Cuboid::new(1., 1., 1.)),
+            material: materials.add(StandardMaterial {
+                base_color: Color::rgba(0.4, 1., 0.5, 0.2),
+                alpha_mode: AlphaMode::Blend,
+                unlit: true,
+                ..default()
+            }),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        SelectionBox,
+    ));
+}
+
+/// Toggles selection mode, which also turns on edit mode to aim. While it's
+/// on, dragging with the left button selects a rectangle of cells, and keys
+/// copy, cut, fill, invert, or clear it. Pasting hands the copied cells to
+/// the stamp so they can be turned and flipped before they're placed.
+#[allow(clippy::type_complexity)]
+pub fn select_region(
+    keys: Res<ButtonInput<KeyCode>>,
+    buttons: Res<ButtonInput<MouseButton>>,
+    mut games: Query<(
+        &mut ConwayGol,
+        &mut Selection,
+        &mut Editor,
+        &mut Stamp,
+        &ChunkGrid,
+        &GlobalTransform,
+    )>,
+    mut selection_box: Query<(&mut Transform, &mut Visibility), With<SelectionBox>>,
+) {
+    let Ok((mut gol, mut selection, mut editor, mut stamp, grid, grid_tform)) =
+        games.get_single_mut()
+    else {
+        return;
+    };
+    if keys.just_pressed(KeyCode::KeyB) {
+        selection.enabled = !selection.enabled;
+        if selection.enabled {
+            editor.enabled = true;
+        } else {
+            selection.anchor = None;
+            selection.area = None;
+        }
+    }
+
+    if selection.enabled && editor.is_enabled() {
+        drag_area(&buttons, &mut selection, editor.target, stamp.is_open());
+        let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+        if let Some(area) = selection.area {
+            if ctrl && keys.just_pressed(KeyCode::KeyC) {
+                selection.clipboard = Some(gol.region(area));
+            }
+            if ctrl && keys.just_pressed(KeyCode::KeyX) {
+                selection.clipboard = Some(gol.region(area));
+                gol.clear_region(area);
+            }
+            if keys.just_pressed(KeyCode::KeyN) {
+                gol.fill_region(area, FILL_DENSITY, &mut thread_rng());
+            }
+            if keys.just_pressed(KeyCode::KeyI) {
+                gol.invert_region(area);
+            }
+            if keys.any_just_pressed([KeyCode::Delete, KeyCode::Backspace]) {
+                gol.clear_region(area);
+            }
+        }
+        if ctrl && keys.just_pressed(KeyCode::KeyV) {
+            if let Some(clipboard) = &selection.clipboard {
+                stamp.paste(clipboard.clone());
+            }
+        }
+    }
+
+    for (mut tform, mut vis) in &mut selection_box {
+        let Some(area) = selection.area.filter(|_| selection.enabled) else {
+            vis.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+        let first = grid.cell_translation(area.top, area.left);
+        let last = grid.cell_translation(area.bottom, area.right);
+        tform.translation = grid_tform.transform_point((first + last) / 2.);
+        tform.scale = (first - last).abs() + Vec3::splat(2.4);
+        vis.set_if_neq(Visibility::Visible);
+    }
+}
+
+/// Starts a selection where the left button goes down and stretches it to
+/// the cell under the crosshair until the button comes up. Clicks that are
+/// stamping don't select.
+fn drag_area(
+    buttons: &ButtonInput<MouseButton>,
+    selection: &mut Selection,
+    target: Option<CubeInd>,
+    stamping: bool,
+) {
+    if !buttons.pressed(MouseButton::Left) || stamping {
+        selection.anchor = None;
+        return;
+    }
+    let Some(target) = target else {
+        return;
+    };
+    if buttons.just_pressed(MouseButton::Left) {
+        selection.anchor = Some(target);
+    }
+    if let Some(anchor) = selection.anchor {
+        let area = Bounds::spanning(
+            (anchor.row as i64, anchor.col as i64),
+            (target.row as i64, target.col as i64),
+        );
+        if selection.area != Some(area) {
+            selection.area = Some(area);
+        }
+    }
+}
+
+#[cfg(test)]
+mod select_tests {
+    use super::{drag_area, Selection};
+    use crate::scene::{sim::Bounds, CubeInd};
+    use bevy::input::{mouse::MouseButton, ButtonInput};
+
+    #[test]
+    fn drags_span_the_area() {
+        let mut buttons = ButtonInput::<MouseButton>::default();
+        let mut selection = Selection::default();
+        let at = |row, col| Some(CubeInd { row, col });
+
+        buttons.press(MouseButton::Left);
+        drag_area(&buttons, &mut selection, at(6, 2), false);
+        buttons.clear();
+        drag_area(&buttons, &mut selection, at(3, 5), false);
+        assert_eq!(selection.area(), Some(Bounds::spanning((3, 2), (6, 5))));
+
+        // Letting go keeps the area, and stamping clicks leave it alone
+        buttons.release(MouseButton::Left);
+        drag_area(&buttons, &mut selection, at(9, 9), false);
+        buttons.press(MouseButton::Left);
+        drag_area(&buttons, &mut selection, at(0, 0), true);
+        assert_eq!(selection.area(), Some(Bounds::spanning((3, 2), (6, 5))));
+    }
+}
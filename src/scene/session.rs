@@ -0,0 +1,356 @@
+use super::{
+    bitboard::BitBoard,
+    pattern::{
+        rle::{read_rle, write_rle},
+        Pattern,
+    },
+    rule::Rule,
+    sim::{ConwayGol, CycleDetector},
+    topology::Topology,
+    undo::UndoStack,
+    CameraRotation, GameTimer, Paused,
+};
+use anyhow::{bail, Context};
+use bevy::prelude::*;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+
+/// First line of every session file
+const SESSION_HEADER: &str = "#Conway session";
+
+/// Where a session is saved when no path is given
+const DEFAULT_SESSION_PATH: &str = "conway.session";
+
+/// Everything needed to resume a run exactly where it was left: the board
+/// and how it evolves, how fast and whether it's running, and where the
+/// camera is looking from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Session {
+    pub cells: BitBoard,
+    pub rule: Rule,
+    pub topology: Topology,
+    pub generation: u64,
+    /// Time between ticks
+    pub tick: Duration,
+    pub paused: bool,
+    pub camera: Transform,
+    pub yaw: f32,
+    pub pitch: f32,
+}
+
+impl Session {
+    /// Writes the session as a header of settings, one per line, followed by
+    /// the board in RLE.
+    pub fn write(&self) -> String {
+        let vec = |v: Vec3| format!("{} {} {}", v.x, v.y, v.z);
+        let quat = |q: Quat| format!("{} {} {} {}", q.x, q.y, q.z, q.w);
+        let settings = [
+            ("generation", self.generation.to_string()),
+            ("tick", self.tick.as_secs_f64().to_string()),
+            ("paused", self.paused.to_string()),
+            ("topology", self.topology.to_string()),
+            ("translation", vec(self.camera.translation)),
+            ("rotation", quat(self.camera.rotation)),
+            ("scale", vec(self.camera.scale)),
+            ("yaw", self.yaw.to_string()),
+            ("pitch", self.pitch.to_string()),
+        ];
+        let mut out = format!("{SESSION_HEADER}\n");
+        for (key, value) in settings {
+            out.push_str(&format!("{key} {value}\n"));
+        }
+        let board = Pattern {
+            rule: Some(self.rule),
+            ..Pattern::new(self.cells.clone())
+        };
+        out.push_str(&write_rle(&board));
+        out
+    }
+
+    /// Parses a session written by `Session::write`.
+    /// Errors if the header is missing, a setting is missing, repeated, or
+    /// malformed, the tick is zero, or the board isn't valid RLE.
+    pub fn read(text: &str) -> anyhow::Result<Self> {
+        let mut lines = text.lines();
+        if lines.next().map(str::trim) != Some(SESSION_HEADER) {
+            bail!("Session files must start with {SESSION_HEADER:?}");
+        }
+
+        let mut settings = Vec::new();
+        let mut board = String::new();
+        for line in lines.by_ref() {
+            let line = line.trim();
+            if line.starts_with('x') {
+                board.push_str(line);
+                board.push('\n');
+                break;
+            }
+            if line.is_empty() {
+                continue;
+            }
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            if settings.iter().any(|(seen, _)| *seen == key) {
+                bail!("Session setting {key:?} appears twice");
+            }
+            settings.push((key, value.trim()));
+        }
+        for line in lines {
+            board.push_str(line);
+            board.push('\n');
+        }
+
+        let setting = |key: &str| {
+            settings
+                .iter()
+                .find(|(seen, _)| *seen == key)
+                .map(|(_, value)| *value)
+                .with_context(|| format!("Session is missing {key:?}"))
+        };
+        let pattern = read_rle(&board).context("Session board is malformed")?;
+        let translation = parse_floats::<3>(setting("translation")?, "translation")?;
+        let rotation = parse_floats::<4>(setting("rotation")?, "rotation")?;
+        let scale = parse_floats::<3>(setting("scale")?, "scale")?;
+        Ok(Session {
+            cells: pattern.cells,
+            rule: pattern.rule.unwrap_or_default(),
+            topology: setting("topology")?.parse()?,
+            generation: parse(setting("generation")?, "generation")?,
+            tick: Duration::try_from_secs_f64(parse(setting("tick")?, "tick")?)
+                .ok()
+                .filter(|tick| !tick.is_zero())
+                .context("Session tick must be a positive number of seconds")?,
+            paused: parse(setting("paused")?, "paused")?,
+            camera: Transform {
+                translation: Vec3::from_array(translation),
+                rotation: Quat::from_array(rotation),
+                scale: Vec3::from_array(scale),
+            },
+            yaw: parse(setting("yaw")?, "yaw")?,
+            pitch: parse(setting("pitch")?, "pitch")?,
+        })
+    }
+}
+
+/// Parses a single setting's value.
+fn parse<T: FromStr>(value: &str, key: &str) -> anyhow::Result<T> {
+    value
+        .parse()
+        .ok()
+        .with_context(|| format!("Session setting {key:?} has bad value {value:?}"))
+}
+
+/// Parses a setting made of `N` numbers separated by spaces.
+fn parse_floats<const N: usize>(value: &str, key: &str) -> anyhow::Result<[f32; N]> {
+    let floats = value
+        .split_whitespace()
+        .map(|part| parse(part, key))
+        .collect::<anyhow::Result<Vec<f32>>>()?;
+    floats
+        .try_into()
+        .ok()
+        .with_context(|| format!("Session setting {key:?} needs {N} numbers, not {value:?}"))
+}
+
+/// Writes a session to a file.
+pub fn save_session(path: impl AsRef<Path>, session: &Session) -> anyhow::Result<()> {
+    let path = path.as_ref();
+    fs::write(path, session.write())
+        .with_context(|| format!("Couldn't write session to {}", path.display()))
+}
+
+/// Reads a session from a file.
+pub fn load_session(path: impl AsRef<Path>) -> anyhow::Result<Session> {
+    let path = path.as_ref();
+    let text = fs::read_to_string(path)
+        .with_context(|| format!("Couldn't read session from {}", path.display()))?;
+    Session::read(&text).with_context(|| format!("Couldn't load session {}", path.display()))
+}
+
+/// The file sessions are saved to and loaded from, and whether to resume
+/// the one there at startup
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub struct SessionFile {
+    path: PathBuf,
+    resume: bool,
+}
+
+impl Default for SessionFile {
+    fn default() -> Self {
+        SessionFile::new(DEFAULT_SESSION_PATH)
+    }
+}
+
+impl SessionFile {
+    /// Returns a session file at `path` that isn't resumed at startup.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        SessionFile {
+            path: path.into(),
+            resume: false,
+        }
+    }
+
+    pub fn with_resume(mut self, resume: bool) -> Self {
+        self.resume = resume;
+        self
+    }
+
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// The game state a session is saved from and loaded into
+type Game = (
+    &'static mut ConwayGol,
+    &'static mut GameTimer,
+    &'static mut Paused,
+    Option<&'static mut UndoStack>,
+    Option<&'static mut CycleDetector>,
+);
+
+/// Saves the session on F5 and loads it back on F9.
+pub fn save_or_load(
+    keys: Res<ButtonInput<KeyCode>>,
+    file: Res<SessionFile>,
+    games: Query<Game>,
+    camera: Query<(&mut Transform, &mut CameraRotation)>,
+) {
+    let result = if keys.just_pressed(KeyCode::F5) {
+        save(&file, games, camera)
+    } else if keys.just_pressed(KeyCode::F9) {
+        resume(&file, games, camera)
+    } else {
+        return;
+    };
+    if let Err(err) = result {
+        error!("{err:#}");
+    }
+}
+
+/// Loads the session at startup if asked to.
+pub fn resume_session(
+    file: Res<SessionFile>,
+    games: Query<Game>,
+    camera: Query<(&mut Transform, &mut CameraRotation)>,
+) {
+    if file.resume {
+        if let Err(err) = resume(&file, games, camera) {
+            error!("{err:#}");
+        }
+    }
+}
+
+fn save(
+    file: &SessionFile,
+    games: Query<Game>,
+    camera: Query<(&mut Transform, &mut CameraRotation)>,
+) -> anyhow::Result<()> {
+    let ((gol, timer, paused, _, _), (tform, rotation)) =
+        (games.get_single()?, camera.get_single()?);
+    let session = Session {
+        cells: gol.board().clone(),
+        rule: gol.rule(),
+        topology: gol.topology(),
+        generation: gol.generation(),
+        tick: timer.0.duration(),
+        paused: paused.is_paused(),
+        camera: *tform,
+        yaw: rotation.yaw,
+        pitch: rotation.pitch,
+    };
+    save_session(&file.path, &session)?;
+    info!("Saved session to {}", file.path.display());
+    Ok(())
+}
+
+/// Loads the session into the game. Recorded edits are forgotten, since
+/// they no longer apply to the board.
+fn resume(
+    file: &SessionFile,
+    mut games: Query<Game>,
+    mut camera: Query<(&mut Transform, &mut CameraRotation)>,
+) -> anyhow::Result<()> {
+    let session = load_session(&file.path)?;
+    let ((mut gol, mut timer, mut paused, stack, detector), (mut tform, mut rotation)) =
+        (games.get_single_mut()?, camera.get_single_mut()?);
+    gol.restore(
+        session.cells,
+        session.rule,
+        session.topology,
+        session.generation,
+    )?;
+    timer.0.set_duration(session.tick);
+    timer.0.reset();
+    *paused = Paused(session.paused);
+    *tform = session.camera;
+    *rotation = CameraRotation {
+        yaw: session.yaw,
+        pitch: session.pitch,
+    };
+    if let Some(mut stack) = stack {
+        stack.clear();
+    }
+    if let Some(mut detector) = detector {
+        detector.reset();
+    }
+    info!("Loaded session from {}", file.path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod session_tests {
+    use super::Session;
+    use crate::scene::{bitboard::BitBoard, topology::Topology};
+    use bevy::prelude::*;
+    use std::time::Duration;
+
+    fn session() -> anyhow::Result<Session> {
+        let mut cells = BitBoard::new(70, 5);
+        cells.set(0, 0, true);
+        cells.set(4, 69, true);
+        Ok(Session {
+            cells,
+            rule: "B36/S23".parse()?,
+            topology: Topology::KleinBottle,
+            generation: 1234,
+            tick: Duration::from_millis(500) / 8,
+            paused: true,
+            camera: Transform::from_xyz(1.5, -8., 292.25).with_rotation(Quat::from_euler(
+                EulerRot::YXZ,
+                1.2,
+                -0.3,
+                0.,
+            )),
+            yaw: 68.75,
+            pitch: -17.2,
+        })
+    }
+
+    #[test]
+    fn round_trip() -> anyhow::Result<()> {
+        let session = session()?;
+        let text = session.write();
+        assert!(text.starts_with("#Conway session\ngeneration 1234\ntick 0.0625\n"));
+        assert_eq!(Session::read(&text)?, session);
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_malformed() -> anyhow::Result<()> {
+        let text = session()?.write();
+        assert!(Session::read(&text.replace("#Conway session", "#Life")).is_err());
+        assert!(Session::read(&text.replace("paused true", "paused maybe")).is_err());
+        assert!(Session::read(&text.replace("tick 0.0625", "tick 0")).is_err());
+        assert!(Session::read(&text.replace("tick 0.0625", "tick -1")).is_err());
+        assert!(Session::read(&text.replace("yaw", "pitch")).is_err());
+        assert!(Session::read(&text.replace("scale 1 1 1", "scale 1 1")).is_err());
+        assert!(Session::read(&text.replace("generation 1234\n", "")).is_err());
+        assert!(Session::read(&text.replace("x = 70", "x = 2")).is_err());
+        Ok(())
+    }
+}
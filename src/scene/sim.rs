@@ -1,266 +1,1018 @@
+use super::{
+    bitboard::{BitBoard, WORD_BITS},
+    rule::Rule,
+    soup::Soup,
+    topology::{Neighbor, Topology},
+};
 use anyhow::bail;
 use bevy::ecs::component::Component;
-use rand::{
-    distributions::{Bernoulli, Distribution},
-    thread_rng,
+use rand::Rng;
+use rayon::prelude::*;
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
 };
 
+mod cycle;
+pub(crate) mod hashlife;
+mod history;
+mod journal;
+
+pub use cycle::{CycleDetector, Settled, DEFAULT_MAX_PERIOD};
+pub use hashlife::{HashLife, MAX_STEP_POW2};
+pub use history::{History, DEFAULT_HISTORY_DEPTH};
+pub use journal::EditCommand;
+
 /// Instantiates and manages board state in Conway's Game of Life.
 #[derive(Debug, Component)]
 pub struct ConwayGol {
-    board: Vec<Vec<bool>>,
-    buffer: Vec<Vec<bool>>,
+    board: BitBoard,
+    buffer: BitBoard,
+    rule: Rule,
+    topology: Topology,
+    /// Scratch space for the board padded by one cell on every side
+    halo: Vec<u64>,
+    /// Cells that flipped in the last tick or were edited since. Edits only
+    /// ever set bits here, since a cell flipped back still needs its
+    /// neighborhood recomputed; `History` keeps its own record of flips.
+    changed: BitBoard,
+    /// Whether every cell must be recomputed on the next tick, as when the
+    /// rule or topology changes
+    stale: bool,
+    /// Number of ticks since the board was built
+    generation: u64,
+    history: History,
+    /// Generation each cell last flipped at, in row-major order
+    flipped_at: Vec<u64>,
+    /// Edits recorded since `begin_edit`, if recording
+    journal: Option<EditCommand>,
 }
 
-impl ConwayGol {
-    /// Returns a Conway Game of Life board of dim x dim dimensions with a random
-    /// initial state.
-    /// Errors if the board dimension is less than four.
-    pub fn build_rand(dim: usize) -> anyhow::Result<Self> {
-        if dim < 4 {
-            bail!("Board dimension must be greater than 3");
+/// Tick and query operations shared by every simulation backend. Positions are
+/// signed so that unbounded backends can grow in every direction.
+pub trait Universe {
+    /// Progresses the universe by one generation.
+    fn tick(&mut self);
+
+    /// Whether the cell at row, col is alive. Cells a finite universe
+    /// can't hold are always dead.
+    fn is_alive(&self, row: i64, col: i64) -> bool;
+
+    /// Sets whether the cell at row, col is alive. Does nothing for cells
+    /// a finite universe can't hold.
+    fn set(&mut self, row: i64, col: i64, is_alive: bool);
+
+    /// Number of live cells in the universe.
+    fn population(&self) -> usize;
+
+    /// The smallest rectangle containing every live cell, or None if
+    /// every cell is dead.
+    fn bounds(&self) -> Option<Bounds>;
+
+    /// Copies a rectangular window of the universe into a dense board whose
+    /// top left cell is the universe's cell at top, left.
+    fn window(&self, top: i64, left: i64, width: usize, height: usize) -> BitBoard {
+        let mut board = BitBoard::new(width, height);
+        for row in 0..height {
+            for col in 0..width {
+                if self.is_alive(top + row as i64, left + col as i64) {
+                    board.set(row, col, true);
+                }
+            }
+        }
+        board
+    }
+}
+
+/// An inclusive rectangle of cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bounds {
+    pub top: i64,
+    pub left: i64,
+    pub bottom: i64,
+    pub right: i64,
+}
+
+impl Bounds {
+    #[inline]
+    pub fn width(&self) -> usize {
+        (self.right - self.left + 1) as usize
+    }
+
+    #[inline]
+    pub fn height(&self) -> usize {
+        (self.bottom - self.top + 1) as usize
+    }
+
+    /// Whether row, col is inside the rectangle.
+    #[inline]
+    pub fn contains(&self, row: i64, col: i64) -> bool {
+        (self.top..=self.bottom).contains(&row) && (self.left..=self.right).contains(&col)
+    }
+
+    /// Grows the rectangle to cover row, col.
+    pub fn include(&mut self, row: i64, col: i64) {
+        self.top = self.top.min(row);
+        self.left = self.left.min(col);
+        self.bottom = self.bottom.max(row);
+        self.right = self.right.max(col);
+    }
+
+    /// The rectangle with opposite corners at two cells.
+    pub fn spanning(a: (i64, i64), b: (i64, i64)) -> Self {
+        Bounds {
+            top: a.0.min(b.0),
+            left: a.1.min(b.1),
+            bottom: a.0.max(b.0),
+            right: a.1.max(b.1),
         }
+    }
+
+    /// The bounds of a set of cells, or None if the set is empty.
+    pub fn enclosing(mut cells: impl Iterator<Item = (i64, i64)>) -> Option<Self> {
+        let (row, col) = cells.next()?;
+        let mut bounds = Bounds {
+            top: row,
+            left: col,
+            bottom: row,
+            right: col,
+        };
+        for (row, col) in cells {
+            bounds.include(row, col);
+        }
+        Some(bounds)
+    }
+}
+
+/// Boards with at least this many cells are ticked in parallel
+const PARALLEL_MIN_CELLS: usize = 256 * 256;
+
+/// Fewest rows a thread is handed at once when ticking in parallel
+const PARALLEL_BAND_ROWS: usize = 16;
 
-        let mut rng = thread_rng();
-        let dist = Bernoulli::new(0.5)?;
-        let mut board = Vec::with_capacity(dim);
-        for _ in 0..dim {
-            board.push(dist.sample_iter(&mut rng).take(dim).collect());
+/// Row, col offsets of the eight cells in a Moore neighborhood
+const NEIGHBOR_OFFSETS: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+];
+
+impl ConwayGol {
+    /// Returns a Conway Game of Life board of width x height dimensions with
+    /// a random initial state.
+    /// Errors if either board dimension is less than four.
+    pub fn build_rand(width: usize, height: usize) -> anyhow::Result<Self> {
+        Self::build_soup(width, height, &Soup::random().with_region(width, height))
+    }
+
+    /// Returns a width x height board with a soup centered on it. The same
+    /// soup always builds the same board.
+    /// Errors if either board dimension is less than four, if the soup doesn't
+    /// fit on the board, or if the soup's settings are invalid.
+    pub fn build_soup(width: usize, height: usize, soup: &Soup) -> anyhow::Result<Self> {
+        if width < 4 || height < 4 {
+            bail!("Board dimensions must be greater than 3, not {width}x{height}");
         }
+        let cells = soup.generate()?;
+        let mut gol = Self::from_board(BitBoard::new(width, height));
+        let top = height.saturating_sub(cells.height()) / 2;
+        let left = width.saturating_sub(cells.width()) / 2;
+        gol.place(&cells, top, left)?;
+        Ok(gol)
+    }
 
-        Ok(Self {
+    /// Wraps an existing board in a bounded simulation following B3/S23.
+    pub fn from_board(board: BitBoard) -> Self {
+        Self {
+            buffer: BitBoard::new(board.width(), board.height()),
+            changed: BitBoard::new(board.width(), board.height()),
+            flipped_at: vec![0; board.width() * board.height()],
             board,
-            buffer: vec![vec![false; dim]; dim],
-        })
+            rule: Rule::default(),
+            topology: Topology::default(),
+            halo: Vec::new(),
+            stale: true,
+            generation: 0,
+            history: History::default(),
+            journal: None,
+        }
+    }
+
+    /// Replaces the rule the board evolves under, which is B3/S23 by default.
+    pub fn with_rule(mut self, rule: Rule) -> Self {
+        self.rule = rule;
+        self.stale = true;
+        self
+    }
+
+    /// Replaces how the board's edges behave, which is bounded by default.
+    pub fn with_topology(mut self, topology: Topology) -> Self {
+        self.topology = topology;
+        self.stale = true;
+        self
+    }
+
+    /// Replaces how many past generations `step_back` can return to.
+    pub fn with_history(mut self, depth: usize) -> Self {
+        self.history = History::new(depth);
+        self
     }
 
     #[inline]
-    pub fn board(&self) -> &Vec<Vec<bool>> {
+    pub fn board(&self) -> &BitBoard {
         &self.board
     }
 
-    /// Progresses the board to its next state following these rules:
+    #[inline]
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    #[inline]
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// The cells that flipped during the last tick or were edited since,
+    /// which are the only cells a renderer needs to update.
+    #[inline]
+    pub fn changed(&self) -> &BitBoard {
+        &self.changed
+    }
+
+    /// A hash of the board's cells and dimensions, which is the same for any
+    /// two identical boards.
+    pub fn board_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.board.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Replaces the board with a freshly generated soup of the same size,
+    /// starting over from generation 0. Recorded as an edit when recording.
+    /// Errors if the soup can't be built on this board.
+    pub fn reseed(&mut self, soup: &Soup) -> anyhow::Result<()> {
+        let fresh = Self::build_soup(self.board.width(), self.board.height(), soup)?;
+        if let Some(journal) = &mut self.journal {
+            journal.restart((self.generation, &self.board), (0, &fresh.board));
+        }
+        self.replace_board(fresh.board);
+        self.restart_at(0);
+        Ok(())
+    }
+
+    /// Reseeds like `reseed` without recording it, even while recording, as
+    /// when the game reseeds itself once the board settles.
+    pub fn reseed_unrecorded(&mut self, soup: &Soup) -> anyhow::Result<()> {
+        let journal = self.journal.take();
+        let reseeded = self.reseed(soup);
+        self.journal = journal;
+        reseeded
+    }
+
+    /// Replaces the board, rule, topology, and generation counter with saved
+    /// ones, as when resuming a session. Cells that differ are marked
+    /// changed, and the history and any edits being recorded are dropped.
+    /// Errors if the saved board isn't the same size as this one.
+    pub fn restore(
+        &mut self,
+        board: BitBoard,
+        rule: Rule,
+        topology: Topology,
+        generation: u64,
+    ) -> anyhow::Result<()> {
+        if (board.width(), board.height()) != (self.board.width(), self.board.height()) {
+            bail!(
+                "A saved {}x{} board can't replace a {}x{} board",
+                board.width(),
+                board.height(),
+                self.board.width(),
+                self.board.height()
+            );
+        }
+        self.replace_board(board);
+        self.rule = rule;
+        self.topology = topology;
+        self.journal = None;
+        self.restart_at(generation);
+        Ok(())
+    }
+
+    /// Replaces every cell at once, marking the ones that differ as changed.
+    fn replace_board(&mut self, board: BitBoard) {
+        let flips = self.board.words().iter().zip(board.words());
+        for (change, (old, new)) in self.changed.words_mut().iter_mut().zip(flips) {
+            *change = old ^ new;
+        }
+        self.board = board;
+    }
+
+    /// Sets the generation counter, forgetting the history and every cell's
+    /// age, which no longer line up with it.
+    fn restart_at(&mut self, generation: u64) {
+        self.generation = generation;
+        self.history.clear();
+        self.flipped_at.fill(0);
+        self.stale = true;
+    }
+
+    /// Starts recording edits and reseeds, though not ticks, into a command
+    /// that `end_edit` hands back. Does nothing if already recording.
+    pub fn begin_edit(&mut self) {
+        self.journal.get_or_insert_with(EditCommand::default);
+    }
+
+    /// Stops recording edits, returning what was recorded unless it was
+    /// nothing.
+    pub fn end_edit(&mut self) -> Option<EditCommand> {
+        self.journal.take().filter(|command| !command.is_empty())
+    }
+
+    /// Reverts an edit: sets each cell it flipped back to how it was. If it
+    /// started the board over, the whole board and generation from before
+    /// are put back instead, along with the cells flipped before that.
+    /// The reversal itself isn't recorded.
+    pub fn undo_edit(&mut self, command: &EditCommand) {
+        let journal = self.journal.take();
+        let flips = match &command.restarted {
+            Some(restart) => {
+                let (_, board, flips) = &restart.before;
+                self.replace_board(board.clone());
+                &command.flips[..*flips]
+            }
+            None => &command.flips[..],
+        };
+        for (row, col, was_alive) in flips.iter().rev() {
+            self.edit(*row, *col, *was_alive);
+        }
+        if let Some(restart) = &command.restarted {
+            self.restart_at(restart.before.0);
+        }
+        self.journal = journal;
+    }
+
+    /// Applies an undone edit again, putting back the whole board from after
+    /// it started the board over if it did. Isn't recorded either.
+    pub fn redo_edit(&mut self, command: &EditCommand) {
+        let journal = self.journal.take();
+        let flips = match &command.restarted {
+            Some(restart) => {
+                let (_, board, flips) = &restart.after;
+                self.replace_board(board.clone());
+                &command.flips[*flips..]
+            }
+            None => &command.flips[..],
+        };
+        for (row, col, was_alive) in flips {
+            self.edit(*row, *col, !was_alive);
+        }
+        if let Some(restart) = &command.restarted {
+            self.restart_at(restart.after.0);
+        }
+        self.journal = journal;
+    }
+
+    /// Number of ticks since the board was built.
+    #[inline]
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Generations since the cell at row, col last flipped: how long it's
+    /// been alive if it's alive, or how long since it died if it's dead.
+    /// Cells that were never alive count from when the board was built, and
+    /// cells that flipped back when stepping back count from then.
+    /// Panics if row, col are out of bounds.
+    #[inline]
+    pub fn age(&self, row: usize, col: usize) -> u64 {
+        assert!(row < self.board.height() && col < self.board.width());
+        self.generation - self.flipped_at[row * self.board.width() + col]
+    }
+
+    /// Ticks that `step_back` can currently undo.
+    #[inline]
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    /// Returns the board to the previous generation. Edits made since that
+    /// tick are undone along with it.
+    /// Returns false if the history has nothing left to undo.
+    pub fn step_back(&mut self) -> bool {
+        if !self.history.undo(&mut self.board, &mut self.changed) {
+            return false;
+        }
+        self.generation -= 1;
+        self.stale = true;
+        self.mark_flipped();
+        true
+    }
+
+    /// Steps backward through the history or ticks forward until the board
+    /// reaches a generation.
+    /// Errors if the generation is older than the history remembers.
+    pub fn seek(&mut self, generation: u64) -> anyhow::Result<()> {
+        let oldest = self.generation - self.history.len() as u64;
+        if generation < oldest {
+            bail!("Generation {generation} is older than the oldest remembered, {oldest}");
+        }
+        while self.generation > generation {
+            self.step_back();
+        }
+        while self.generation < generation {
+            self.tick();
+        }
+        Ok(())
+    }
+
+    /// Sets one cell, recording it as changed if it flips.
+    fn edit(&mut self, row: usize, col: usize, is_alive: bool) {
+        if self.board.get(row, col) != is_alive {
+            self.board.set(row, col, is_alive);
+            self.changed.set(row, col, true);
+            let word_ind = row * self.board.words_per_row() + col / WORD_BITS;
+            self.history.amend(word_ind, 1 << (col % WORD_BITS));
+            self.flipped_at[row * self.board.width() + col] = self.generation;
+            if let Some(journal) = &mut self.journal {
+                journal.flip(row, col, !is_alive);
+            }
+        }
+    }
+
+    /// Overwrites the rectangle of the board whose top left cell is at top, left
+    /// with a smaller board, such as a pattern loaded from a file.
+    /// Errors if the cells don't fit on the board at that position.
+    pub fn place(&mut self, cells: &BitBoard, top: usize, left: usize) -> anyhow::Result<()> {
+        if top + cells.height() > self.board.height() || left + cells.width() > self.board.width() {
+            bail!(
+                "A {}x{} pattern at row {top}, col {left} doesn't fit on a {}x{} board",
+                cells.width(),
+                cells.height(),
+                self.board.width(),
+                self.board.height()
+            );
+        }
+        for row in 0..cells.height() {
+            for col in 0..cells.width() {
+                self.edit(top + row, left + col, cells.get(row, col));
+            }
+        }
+        Ok(())
+    }
+
+    /// Like `place`, but the cells may hang off the board at any position,
+    /// and those that do are dropped.
+    pub fn stamp(&mut self, cells: &BitBoard, top: i64, left: i64) {
+        for row in 0..cells.height() {
+            for col in 0..cells.width() {
+                Universe::set(
+                    self,
+                    top + row as i64,
+                    left + col as i64,
+                    cells.get(row, col),
+                );
+            }
+        }
+    }
+
+    /// Copies the cells inside a rectangle, reading any off the board as dead.
+    pub fn region(&self, area: Bounds) -> BitBoard {
+        let mut cells = BitBoard::new(area.width(), area.height());
+        for (row, col) in self.cells_in(area) {
+            if self.board.get(row, col) {
+                cells.set(
+                    (row as i64 - area.top) as usize,
+                    (col as i64 - area.left) as usize,
+                    true,
+                );
+            }
+        }
+        cells
+    }
+
+    /// Kills every cell inside a rectangle.
+    pub fn clear_region(&mut self, area: Bounds) {
+        for (row, col) in self.cells_in(area) {
+            self.edit(row, col, false);
+        }
+    }
+
+    /// Flips every cell inside a rectangle.
+    pub fn invert_region(&mut self, area: Bounds) {
+        for (row, col) in self.cells_in(area) {
+            self.edit(row, col, !self.board.get(row, col));
+        }
+    }
+
+    /// Replaces the cells inside a rectangle with random ones, each alive
+    /// with probability `density`.
+    pub fn fill_region(&mut self, area: Bounds, density: f64, rng: &mut impl Rng) {
+        for (row, col) in self.cells_in(area) {
+            self.edit(row, col, rng.gen_bool(density));
+        }
+    }
+
+    /// The cells of a rectangle that are on the board, in row-major order.
+    fn cells_in(&self, area: Bounds) -> impl Iterator<Item = (usize, usize)> {
+        let clamp = |value: i64, len: usize| value.clamp(0, len as i64) as usize;
+        let rows =
+            clamp(area.top, self.board.height())..clamp(area.bottom + 1, self.board.height());
+        let cols = clamp(area.left, self.board.width())..clamp(area.right + 1, self.board.width());
+        rows.flat_map(move |row| cols.clone().map(move |col| (row, col)))
+    }
+
+    /// Progresses the board to its next state following the board's rule.
+    /// By default, these are the rules:
     /// https://en.wikipedia.org/wiki/Conway%27s_Game_of_Life
+    /// Large boards are split into bands of rows that are ticked in parallel.
     pub fn tick(&mut self) {
-        // the new board is written into the buffer, and then the vectors are swapped
-        for row in 0..self.board.len() {
-            for col in 0..self.board[0].len() {
-                let nb_ct = self.count_neighbors(row, col);
-                if !self.board[row][col] {
-                    self.buffer[row][col] = nb_ct == 3;
+        let parallel = self.board.width() * self.board.height() >= PARALLEL_MIN_CELLS;
+        self.step(parallel);
+    }
+
+    /// Progresses the board like `tick`, always spreading rows across
+    /// rayon's thread pool.
+    pub fn tick_par(&mut self) {
+        self.step(true);
+    }
+
+    fn step(&mut self, parallel: bool) {
+        // The new board is written into the buffer, and then the boards are swapped.
+        // Each u64 holds 64 cells, and all 64 are advanced at once by summing
+        // shifted copies of the neighboring halo rows into bit-sliced counters.
+        // A word whose neighborhood didn't change last tick can't change on this
+        // one, so it's copied over instead of recomputed.
+        self.fill_halo();
+        self.generation += 1;
+        let wpr = self.board.words_per_row();
+        if wpr == 0 {
+            self.history.record(&self.changed);
+            return;
+        }
+        let height = self.board.height();
+        let halo_wpr = self.halo_words_per_row();
+        let halo = &self.halo;
+        let halo_row = |row: usize| &halo[(row * halo_wpr)..((row + 1) * halo_wpr)];
+        let (rule, board, changed) = (self.rule, &self.board, &self.changed);
+
+        let stale = self.stale;
+        // Changes along an edge can reach cells along the opposite edge
+        let border_dirty = self.topology.wraps()
+            && (0..height).any(|row| {
+                let words = changed.row(row);
+                let is_border = row == 0 || row + 1 == height;
+                (is_border && words.iter().any(|word| *word != 0))
+                    || words[0] != 0
+                    || words[wpr - 1] != 0
+            });
+        let is_dirty = |row: usize, word_ind: usize| {
+            if stale {
+                return true;
+            }
+            let on_border = row == 0 || row + 1 == height || word_ind == 0 || word_ind + 1 == wpr;
+            if border_dirty && on_border {
+                return true;
+            }
+            let words = word_ind.saturating_sub(1)..(word_ind + 2).min(wpr);
+            (row.saturating_sub(1)..(row + 2).min(height)).any(|nbr_row| {
+                changed.row(nbr_row)[words.clone()]
+                    .iter()
+                    .any(|word| *word != 0)
+            })
+        };
+
+        // Rows only read the halo, so each one can be written independently
+        let step_row = |(row, out): (usize, &mut [u64])| {
+            // Halo row i holds board row i - 1
+            let (above, middle, below) = (halo_row(row), halo_row(row + 1), halo_row(row + 2));
+            for (word_ind, out_word) in out.iter_mut().enumerate() {
+                if !is_dirty(row, word_ind) {
+                    *out_word = board.row(row)[word_ind];
                     continue;
                 }
-                self.buffer[row][col] = match nb_ct {
-                    ct if ct < 2 => false,
-                    ct if ct == 2 || ct == 3 => true,
-                    ct if ct > 3 => false,
-                    _ => panic!("Cases above should have been exhaustive"),
-                };
+                let mut sum = [0u64; 4];
+                for nbr_row in [above, below] {
+                    let (west, center, east) = shifted_words(nbr_row, word_ind);
+                    add_lane(&mut sum, west);
+                    add_lane(&mut sum, center);
+                    add_lane(&mut sum, east);
+                }
+                let (west, alive, east) = shifted_words(middle, word_ind);
+                add_lane(&mut sum, west);
+                add_lane(&mut sum, east);
+
+                *out_word = apply_rule(rule, alive, &sum) & board.word_mask(word_ind);
             }
+        };
+
+        let words = self.buffer.words_mut();
+        if parallel {
+            words
+                .par_chunks_exact_mut(wpr)
+                .enumerate()
+                .with_min_len(PARALLEL_BAND_ROWS)
+                .for_each(step_row);
+        } else {
+            words.chunks_exact_mut(wpr).enumerate().for_each(step_row);
+        }
+
+        let flips = self.board.words().iter().zip(self.buffer.words());
+        for (change, (old, new)) in self.changed.words_mut().iter_mut().zip(flips) {
+            *change = old ^ new;
         }
+        self.history.record(&self.changed);
+        self.stale = false;
         std::mem::swap(&mut self.board, &mut self.buffer);
+        self.mark_flipped();
     }
 
-    /// Counts the live neighbors a row, col pair has.
+    /// Restarts the ages of the cells marked as changed.
+    fn mark_flipped(&mut self) {
+        let width = self.board.width();
+        for (row, col) in self.changed.iter_alive() {
+            self.flipped_at[row * width + col] = self.generation;
+        }
+    }
+
+    /// Counts the live neighbors a row, col pair has, looking past the
+    /// edges according to the board's topology.
     /// Panics if row, col are out of bounds.
-    fn count_neighbors(&self, row: usize, col: usize) -> usize {
-        assert!(row < self.board.len());
-        assert!(col < self.board[0].len());
-        let mut ct = 0;
-        for nbr in GridIter::new(row, col) {
-            let Some(is_alive) = self.board.get(nbr.row).and_then(|row| row.get(nbr.col)) else {
-                continue;
-            };
-            if *is_alive {
-                ct += 1;
-            }
+    pub fn count_neighbors(&self, row: usize, col: usize) -> usize {
+        assert!(row < self.board.height());
+        assert!(col < self.board.width());
+        NEIGHBOR_OFFSETS
+            .iter()
+            .filter(|(d_row, d_col)| self.cell_state(row as isize + d_row, col as isize + d_col))
+            .count()
+    }
+
+    /// Whether the cell at a position at most one step off the board is alive.
+    fn cell_state(&self, row: isize, col: isize) -> bool {
+        let (width, height) = (self.board.width(), self.board.height());
+        match self.topology.resolve(row, col, width, height) {
+            Neighbor::Cell { row, col } => self.board.get(row, col),
+            Neighbor::Dead => false,
+            Neighbor::Alive => true,
         }
-        ct
     }
-}
 
-/// Represents a row, column position in a 2d grid
-#[derive(Debug, PartialEq, Eq)]
-struct Coord {
-    row: usize,
-    col: usize,
-}
+    /// Words in each halo row, which is two cells wider than a board row
+    #[inline]
+    fn halo_words_per_row(&self) -> usize {
+        (self.board.width() + 2).div_ceil(WORD_BITS)
+    }
+
+    /// Copies the board into the halo buffer shifted one cell down and to the
+    /// right, then fills the border cells according to the topology.
+    fn fill_halo(&mut self) {
+        let (width, height) = (self.board.width(), self.board.height());
+        let halo_wpr = self.halo_words_per_row();
+        let mut halo = std::mem::take(&mut self.halo);
+        halo.clear();
+        halo.resize(halo_wpr * (height + 2), 0);
 
-/// Iterates over the coordinates adjacent to an origin in a 2d grid.  
-#[derive(Debug)]
-struct GridIter {
-    origin_row: usize,
-    origin_col: usize,
-    curr_row: usize,
-    curr_col: usize,
+        for (halo_ind, dst) in halo.chunks_exact_mut(halo_wpr).enumerate() {
+            let row = halo_ind as isize - 1;
+            if 0 <= row && row < height as isize {
+                for (word_ind, word) in self.board.row(row as usize).iter().enumerate() {
+                    dst[word_ind] |= word << 1;
+                    if word_ind + 1 < halo_wpr {
+                        dst[word_ind + 1] |= word >> (WORD_BITS - 1);
+                    }
+                }
+            } else {
+                for col in 0..width {
+                    if self.cell_state(row, col as isize) {
+                        dst[(col + 1) / WORD_BITS] |= 1 << ((col + 1) % WORD_BITS);
+                    }
+                }
+            }
+            for (col, halo_col) in [(-1, 0), (width as isize, width + 1)] {
+                if self.cell_state(row, col) {
+                    dst[halo_col / WORD_BITS] |= 1 << (halo_col % WORD_BITS);
+                }
+            }
+        }
+        self.halo = halo;
+    }
 }
 
-impl GridIter {
-    /// Iterates the 2d coordinates around a given row and column. Terminates
-    /// in at most 8 iterations. Skips negative indices, but it's the caller's
-    /// duty to ensure returned coordinates don't exceed vector bounds.
-    pub fn new(origin_row: usize, origin_col: usize) -> Self {
-        let curr_row = origin_row.saturating_sub(1);
-        let mut curr_col = origin_col.saturating_sub(1);
+impl Universe for ConwayGol {
+    fn tick(&mut self) {
+        ConwayGol::tick(self);
+    }
 
-        if curr_row == origin_row {
-            // skip all of the top row if we can't go up
-            curr_col = origin_col + 1;
-        }
+    fn is_alive(&self, row: i64, col: i64) -> bool {
+        let (Ok(row), Ok(col)) = (usize::try_from(row), usize::try_from(col)) else {
+            return false;
+        };
+        row < self.board.height() && col < self.board.width() && self.board.get(row, col)
+    }
 
-        GridIter {
-            origin_row,
-            origin_col,
-            curr_row,
-            curr_col,
+    fn set(&mut self, row: i64, col: i64, is_alive: bool) {
+        let (Ok(row), Ok(col)) = (usize::try_from(row), usize::try_from(col)) else {
+            return;
+        };
+        if row < self.board.height() && col < self.board.width() {
+            self.edit(row, col, is_alive);
         }
     }
 
-    /// Returns the current iterator state as a coordinate.
-    fn curr_coord(&self) -> Coord {
-        Coord {
-            row: self.curr_row,
-            col: self.curr_col,
-        }
+    fn population(&self) -> usize {
+        self.board.population()
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        Bounds::enclosing(
+            self.board
+                .iter_alive()
+                .map(|(row, col)| (row as i64, col as i64)),
+        )
     }
 }
 
-impl Iterator for GridIter {
-    type Item = Coord;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.curr_row == self.origin_row && self.curr_col == self.origin_col {
-            // At origin
-            return None;
-        }
-        if self.curr_row < self.origin_row && self.curr_col <= self.origin_col {
-            // top left, top mid
-            let res = self.curr_coord();
-            self.curr_col += 1;
-            return Some(res);
-        }
-        if self.origin_col < self.curr_col && self.curr_row <= self.origin_row {
-            // top right
-            // mid right
-            let res = self.curr_coord();
-            self.curr_row += 1;
-            return Some(res);
-        }
-        if self.origin_col <= self.curr_col && self.origin_row < self.curr_row {
-            // bottom right, bottom mid
-            let next_col = self.curr_col.saturating_sub(1);
-            if next_col < self.curr_col {
-                let res = self.curr_coord();
-                self.curr_col = next_col;
-                return Some(res);
-            }
-            // bottom mid with a nonexistent left side
-            let res = self.curr_coord();
-            self.curr_row -= 1;
-            debug_assert_eq!(self.curr_row, self.origin_row);
-            debug_assert_eq!(self.curr_col, self.origin_col);
-            return Some(res);
-        }
-        if self.origin_row < self.curr_row && self.curr_col < self.origin_col {
-            // bottom left
-            let res = self.curr_coord();
-            self.curr_row -= 1;
-            return Some(res);
-        }
-        debug_assert_eq!(self.curr_row, self.origin_row);
-        // left mid
-        let res = self.curr_coord();
-        self.curr_col += 1;
-        debug_assert_eq!(self.curr_col, self.origin_col);
-        debug_assert_eq!(self.curr_row, self.origin_row);
-        Some(res)
+/// Returns the cells of a halo row that line up with word `word_ind` of a board
+/// row, as seen from their west neighbors, themselves, and their east neighbors.
+#[inline]
+fn shifted_words(halo_row: &[u64], word_ind: usize) -> (u64, u64, u64) {
+    let word = halo_row[word_ind];
+    let next = halo_row.get(word_ind + 1).copied().unwrap_or(0);
+    let west = word;
+    let center = (word >> 1) | (next << (WORD_BITS - 1));
+    let east = (word >> 2) | (next << (WORD_BITS - 2));
+    (west, center, east)
+}
+
+/// Adds a one-bit value to each of the 64 lanes of a bit-sliced counter,
+/// where `sum[k]` holds bit k of every lane's count.
+#[inline]
+fn add_lane(sum: &mut [u64; 4], mut carry: u64) {
+    for bit in sum.iter_mut() {
+        let next_carry = *bit & carry;
+        *bit ^= carry;
+        carry = next_carry;
+    }
+}
+
+/// Computes the next state of 64 cells at once given their current states
+/// and bit-sliced neighbor counts.
+#[inline]
+fn apply_rule(rule: Rule, alive: u64, sum: &[u64; 4]) -> u64 {
+    let mut next = 0;
+    for nb_ct in 0..=8 {
+        let births = rule.births(nb_ct);
+        let survives = rule.survives(nb_ct);
+        if !births && !survives {
+            continue;
+        }
+        let matches = sum.iter().enumerate().fold(u64::MAX, |acc, (bit, lane)| {
+            acc & if nb_ct >> bit & 1 == 1 { *lane } else { !*lane }
+        });
+        if births {
+            next |= matches & !alive;
+        }
+        if survives {
+            next |= matches & alive;
+        }
     }
+    next
 }
 
 #[cfg(test)]
 mod conway_tests {
-    use super::{ConwayGol, Coord, GridIter};
+    use super::{Bounds, ConwayGol, Universe};
+    use crate::scene::{bitboard::BitBoard, rule::Rule, soup::Soup, topology::Topology};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
 
-    impl Coord {
-        pub fn new(row: usize, col: usize) -> Self {
-            Coord { row, col }
-        }
+    #[test]
+    fn board_init() -> anyhow::Result<()> {
+        let cw = ConwayGol::build_rand(4, 4)?;
+        assert_eq!(cw.board.height(), cw.buffer.height());
+        assert_eq!(cw.board.width(), cw.buffer.width());
+        assert_eq!(cw.board.height(), cw.board.width());
+
+        let cw = ConwayGol::build_rand(130, 5)?;
+        assert_eq!((cw.board.width(), cw.board.height()), (130, 5));
+        assert_eq!((cw.buffer.width(), cw.buffer.height()), (130, 5));
+        assert!(ConwayGol::build_rand(130, 3).is_err());
+        Ok(())
     }
 
+    /// A seeded soup rebuilds the same board, centered in its region
     #[test]
-    fn board_init() -> anyhow::Result<()> {
-        let cw = ConwayGol::build_rand(4)?;
-        assert_eq!(cw.board.len(), cw.buffer.len());
-        assert_eq!(cw.board[0].len(), cw.buffer[0].len());
-        assert_eq!(cw.board.len(), cw.board[0].len());
+    fn soup_init() -> anyhow::Result<()> {
+        let soup = Soup::new(2024).with_region(10, 6).with_density(1.);
+        let cw = ConwayGol::build_soup(20, 20, &soup)?;
+        assert_eq!(cw.board().population(), 60);
+        assert_eq!(
+            cw.bounds(),
+            Some(Bounds {
+                top: 7,
+                left: 5,
+                bottom: 12,
+                right: 14
+            })
+        );
+
+        let soup = soup.with_density(0.3);
+        let a = ConwayGol::build_soup(30, 20, &soup)?;
+        let b = ConwayGol::build_soup(30, 20, &soup)?;
+        assert_eq!(a.board(), b.board());
+        assert!(ConwayGol::build_soup(30, 5, &soup).is_err());
+        Ok(())
+    }
+
+    /// Stamps overwrite the cells under them and drop what's off the board
+    #[test]
+    fn stamps_clip() -> anyhow::Result<()> {
+        let mut cw = ConwayGol::from_board(BitBoard::new(4, 4));
+        cw.stamp(
+            &BitBoard::from_rows(&[vec![true; 3], vec![true; 3]])?,
+            2,
+            -1,
+        );
+        assert_eq!(cw.board().population(), 4);
+        assert!(cw.board().get(3, 0) && cw.board().get(2, 1));
+
+        cw.stamp(&BitBoard::from_rows(&[vec![false, true]])?, 3, 0);
+        assert_eq!(cw.board().population(), 3);
+        assert!(!cw.board().get(3, 0) && cw.board().get(3, 1));
+        assert!(cw.place(&BitBoard::new(2, 2), 3, 3).is_err());
+        Ok(())
+    }
+
+    /// Region edits only touch the part of their rectangle on the board
+    #[test]
+    fn region_edits() -> anyhow::Result<()> {
+        let mut cw = ConwayGol::from_board(BitBoard::from_rows(&[
+            vec![true, true, false, false],
+            vec![false, true, false, true],
+            vec![false, false, true, true],
+        ])?);
+        let area = Bounds::spanning((2, 3), (-1, 2));
+        assert_eq!(area.width(), 2);
+        assert_eq!(area.height(), 4);
+        assert_eq!(
+            cw.region(area).to_rows(),
+            vec![
+                vec![false, false],
+                vec![false, false],
+                vec![false, true],
+                vec![true, true],
+            ]
+        );
+
+        cw.invert_region(area);
+        assert_eq!(cw.region(area).population(), 3);
+        assert_eq!(cw.board().population(), 6);
+        cw.clear_region(area);
+        assert_eq!(cw.board().population(), 3);
+
+        let mut rng = StdRng::seed_from_u64(22);
+        cw.fill_region(Bounds::spanning((0, 0), (9, 9)), 1., &mut rng);
+        assert_eq!(cw.board().population(), 12);
+        cw.fill_region(Bounds::spanning((0, 0), (2, 1)), 0., &mut rng);
+        assert_eq!(cw.board().population(), 6);
         Ok(())
     }
 
-    /// Tests the grid iterator on a fully in-bounds 9x9 chunk
+    /// Recorded edits revert and reapply no matter how many ticks pass
     #[test]
-    fn grid_iter_basic() {
-        let mut it = GridIter::new(1, 1);
-        assert_eq!(it.next().unwrap(), Coord::new(0, 0), "top left");
-        assert_eq!(it.next().unwrap(), Coord::new(0, 1), "top mid");
-        assert_eq!(it.next().unwrap(), Coord::new(0, 2), "top right");
-        assert_eq!(it.next().unwrap(), Coord::new(1, 2), "mid right");
-        assert_eq!(it.next().unwrap(), Coord::new(2, 2), "bottom right");
-        assert_eq!(it.next().unwrap(), Coord::new(2, 1), "bottom mid");
-        assert_eq!(it.next().unwrap(), Coord::new(2, 0), "bottom left");
-        assert_eq!(it.next().unwrap(), Coord::new(1, 0), "mid left");
-        assert_eq!(it.next(), None, "Done");
-    }
-
-    /// Grid iterator on the top left corner
+    fn edits_undo_and_redo() -> anyhow::Result<()> {
+        let mut cw = ConwayGol::from_board(BitBoard::new(12, 12));
+        cw.begin_edit();
+        assert_eq!(cw.end_edit(), None);
+
+        cw.begin_edit();
+        cw.set(0, 0, true);
+        cw.set(0, 0, false);
+        cw.stamp(&BitBoard::from_rows(&[vec![true; 2], vec![true; 2]])?, 3, 3);
+        let block = cw.end_edit().expect("Stamping a block flips cells");
+        assert_eq!(block.len(), 6);
+        let with_block = cw.board().clone();
+
+        cw.tick();
+        cw.tick();
+        cw.begin_edit();
+        cw.reseed(&Soup::new(23).with_region(8, 8))?;
+        let reseed = cw.end_edit().expect("Reseeding flips cells");
+        let reseeded = cw.board().clone();
+        cw.set(11, 11, !cw.board().get(11, 11));
+        assert_eq!(cw.generation(), 0);
+
+        // Undoing the reseed puts back the whole board and generation from
+        // before it, so the later toggle goes too
+        cw.undo_edit(&reseed);
+        assert_eq!(cw.generation(), 2);
+        assert_eq!(cw.board(), &with_block);
+        cw.undo_edit(&block);
+        assert_eq!(cw.board().population(), 0);
+
+        cw.redo_edit(&block);
+        cw.redo_edit(&reseed);
+        assert_eq!((cw.board(), cw.generation()), (&reseeded, 0));
+        assert_eq!(cw.end_edit(), None);
+        Ok(())
+    }
+
+    /// A reseed undone after ticks puts back exactly the board from before
+    /// it, and one redone after ticks exactly the board it left. Reseeds
+    /// made without recording aren't recorded.
     #[test]
-    fn grid_iter_top_left() {
-        let mut it = GridIter::new(0, 0);
-        assert_eq!(it.next().unwrap(), Coord::new(0, 1), "mid right");
-        assert_eq!(it.next().unwrap(), Coord::new(1, 1), "bottom right");
-        assert_eq!(it.next().unwrap(), Coord::new(1, 0), "bottom mid");
-        assert_eq!(it.next(), None, "Done");
+    fn reseeds_undo_after_ticks() -> anyhow::Result<()> {
+        let mut cw = ConwayGol::from_board(BitBoard::new(16, 16));
+        cw.stamp(&BitBoard::from_rows(&[vec![true; 3]])?, 7, 6);
+        cw.tick();
+        let before = cw.board().clone();
+
+        cw.begin_edit();
+        cw.set(0, 0, true);
+        cw.reseed(&Soup::new(5).with_region(12, 12))?;
+        let reseeded = cw.board().clone();
+        cw.set(15, 15, !reseeded.get(15, 15));
+        let reseed = cw.end_edit().expect("Reseeding is recorded");
+        let after = cw.board().clone();
+        for _ in 0..5 {
+            cw.tick();
+        }
+        cw.undo_edit(&reseed);
+        assert_eq!((cw.board(), cw.generation()), (&before, 1));
+
+        for _ in 0..3 {
+            cw.tick();
+        }
+        cw.redo_edit(&reseed);
+        assert_eq!((cw.board(), cw.generation()), (&after, 0));
+        cw.tick();
+        let mut full = ConwayGol::from_board(after);
+        full.tick();
+        assert_eq!(cw.board(), full.board());
+
+        cw.begin_edit();
+        cw.reseed_unrecorded(&Soup::new(6).with_region(12, 12))?;
+        assert_eq!(cw.end_edit(), None);
+        Ok(())
     }
 
-    /// Grid iterator on the top row
+    /// Restoring a saved board replaces everything and flags what differs
     #[test]
-    fn grid_iter_top() {
-        let mut it = GridIter::new(0, 1);
-        assert_eq!(it.next().unwrap(), Coord::new(0, 2), "mid right");
-        assert_eq!(it.next().unwrap(), Coord::new(1, 2), "bottom right");
-        assert_eq!(it.next().unwrap(), Coord::new(1, 1), "bottom mid");
-        assert_eq!(it.next().unwrap(), Coord::new(1, 0), "bottom left");
-        assert_eq!(it.next().unwrap(), Coord::new(0, 0), "left mid");
-        assert_eq!(it.next(), None, "Done");
+    fn restore_saved() -> anyhow::Result<()> {
+        let mut cw = ConwayGol::from_board(BitBoard::new(6, 6));
+        cw.set(0, 0, true);
+        let mut saved = BitBoard::new(6, 6);
+        saved.set(5, 5, true);
+        cw.restore(saved.clone(), "B36/S23".parse()?, Topology::Torus, 40)?;
+        assert_eq!(cw.board(), &saved);
+        assert_eq!(
+            cw.changed().iter_alive().collect::<Vec<_>>(),
+            vec![(0, 0), (5, 5)]
+        );
+        assert_eq!(cw.rule().to_string(), "B36/S23");
+        assert_eq!((cw.topology(), cw.generation()), (Topology::Torus, 40));
+        assert!(cw.history().is_empty());
+
+        let wrong_size = BitBoard::new(6, 7);
+        assert!(cw
+            .restore(wrong_size, Rule::default(), Topology::Bounded, 0)
+            .is_err());
+        Ok(())
     }
 
-    /// Grid iterator on the bottom left corner
+    /// Neighbor counts along the border of a 3x3 board under each topology
     #[test]
-    fn grid_iter_bottom_left() {
-        let mut it = GridIter::new(10, 0).into_iter();
-        assert_eq!(it.next().unwrap(), Coord::new(9, 0), "top mid");
-        assert_eq!(it.next().unwrap(), Coord::new(9, 1), "top right");
-        assert_eq!(it.next().unwrap(), Coord::new(10, 1), "mid right");
-        assert_eq!(it.next().unwrap(), Coord::new(11, 1), "bottom right");
-        assert_eq!(it.next().unwrap(), Coord::new(11, 0), "bottom mid");
-        assert_eq!(it.next(), None, "Done");
+    fn count_neighbors_at_edges() -> anyhow::Result<()> {
+        let board = BitBoard::from_rows(&[
+            vec![true, false, false],
+            vec![false, false, false],
+            vec![false, false, true],
+        ])?;
+        let counts = |topology| {
+            let cw = ConwayGol::from_board(board.clone()).with_topology(topology);
+            [(0, 0), (0, 1), (1, 1), (2, 2)].map(|(row, col)| cw.count_neighbors(row, col))
+        };
+        assert_eq!(counts(Topology::Bounded), [0, 1, 2, 0]);
+        assert_eq!(counts(Topology::AliveEdge), [5, 4, 2, 5]);
+        assert_eq!(counts(Topology::Torus), [1, 2, 2, 1]);
+        Ok(())
     }
 
     /// Verifies correctness of GoL on a 9x9 grid for a few ticks
     #[test]
-    fn conway_9x9() {
-        let dim = 3;
-        let mut cw = ConwayGol {
-            board: vec![
-                vec![false, true, false],
-                vec![false, true, true],
-                vec![false, false, true],
-            ],
-            buffer: vec![vec![false; dim]; dim],
-        };
+    fn conway_9x9() -> anyhow::Result<()> {
+        let mut cw = ConwayGol::from_board(BitBoard::from_rows(&[
+            vec![false, true, false],
+            vec![false, true, true],
+            vec![false, false, true],
+        ])?);
 
         cw.tick();
         assert_eq!(
-            cw.board,
+            cw.board.to_rows(),
             vec![
                 vec![false, true, true],
                 vec![false, true, true],
@@ -270,7 +1022,7 @@ mod conway_tests {
 
         cw.tick();
         assert_eq!(
-            cw.board,
+            cw.board.to_rows(),
             vec![
                 vec![false, true, true],
                 vec![true, false, false],
@@ -280,12 +1032,450 @@ mod conway_tests {
 
         cw.tick();
         assert_eq!(
-            cw.board,
+            cw.board.to_rows(),
             vec![
                 vec![false, true, false],
                 vec![true, false, false],
                 vec![false, true, false],
             ]
         );
+        Ok(())
+    }
+
+    /// HighLife's extra B6 birth condition is applied by tick
+    #[test]
+    fn tick_uses_rule() -> anyhow::Result<()> {
+        let board = BitBoard::from_rows(&[
+            vec![true, true, true, false],
+            vec![true, false, true, false],
+            vec![true, false, false, false],
+            vec![false, false, false, false],
+        ])?;
+        let mut life = ConwayGol::from_board(board.clone());
+        let mut highlife = ConwayGol::from_board(board).with_rule("B36/S23".parse()?);
+
+        life.tick();
+        highlife.tick();
+        // (1, 1) has six live neighbors
+        assert!(!life.board.get(1, 1));
+        assert!(highlife.board.get(1, 1));
+        assert_eq!(highlife.rule(), "B36/S23".parse()?);
+        Ok(())
+    }
+
+    /// A glider keeps its shape while crossing the boundary between words
+    #[test]
+    fn glider_crosses_words() -> anyhow::Result<()> {
+        let mut board = BitBoard::new(80, 8);
+        for (row, col) in [(0, 61), (1, 62), (2, 60), (2, 61), (2, 62)] {
+            board.set(row, col, true);
+        }
+        let mut cw = ConwayGol::from_board(board);
+        for _ in 0..16 {
+            cw.tick();
+        }
+        let alive: Vec<_> = cw.board.iter_alive().collect();
+        assert_eq!(alive, vec![(4, 65), (5, 66), (6, 64), (6, 65), (6, 66)]);
+        Ok(())
+    }
+
+    /// The word-parallel tick matches a cell-by-cell reference on random boards
+    #[test]
+    fn tick_matches_reference() -> anyhow::Result<()> {
+        let mut rng = StdRng::seed_from_u64(175);
+        let topologies = [
+            Topology::Bounded,
+            Topology::AliveEdge,
+            Topology::Torus,
+            Topology::KleinBottle,
+            Topology::CrossSurface,
+        ];
+        let rules = ["B3/S23", "B36/S23", "B2/S", "B0123478/S01234678"];
+        for (rule, topology) in rules.iter().flat_map(|r| topologies.map(|t| (r, t))) {
+            let (width, height) = (rng.gen_range(1..200), rng.gen_range(1..40));
+            let mut board = BitBoard::new(width, height);
+            for row in 0..height {
+                for col in 0..width {
+                    board.set(row, col, rng.gen_bool(0.4));
+                }
+            }
+            let mut cw = ConwayGol::from_board(board)
+                .with_rule(rule.parse()?)
+                .with_topology(topology);
+            for _ in 0..8 {
+                let mut expected = BitBoard::new(width, height);
+                for row in 0..height {
+                    for col in 0..width {
+                        let nb_ct = cw.count_neighbors(row, col);
+                        expected.set(row, col, cw.rule.next_state(cw.board.get(row, col), nb_ct));
+                    }
+                }
+                cw.tick();
+                assert_eq!(cw.board, expected, "rule {rule}, {topology:?}");
+            }
+        }
+        Ok(())
+    }
+
+    /// Places a glider heading in the given row, col direction with its
+    /// bounding box's top left corner at row, col.
+    fn glider(board: &mut BitBoard, row: usize, col: usize, d_row: isize, d_col: isize) {
+        let cells = [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)];
+        for (r, c) in cells {
+            let r = if d_row > 0 { r } else { 2 - r };
+            let c = if d_col > 0 { c } else { 2 - c };
+            board.set(row + r, col + c, true);
+        }
+    }
+
+    /// Parallel ticks produce exactly the boards serial ticks do, whatever
+    /// the board's shape, rule, and topology
+    #[test]
+    fn parallel_tick_matches_serial() -> anyhow::Result<()> {
+        let mut rng = StdRng::seed_from_u64(2011);
+        let topologies = [
+            Topology::Bounded,
+            Topology::AliveEdge,
+            Topology::Torus,
+            Topology::KleinBottle,
+            Topology::CrossSurface,
+        ];
+        let rules = ["B3/S23", "B36/S23", "B2/S", "B0123478/S01234678"];
+        for case in 0..40 {
+            let (width, height) = (rng.gen_range(1..300), rng.gen_range(1..120));
+            let density = rng.gen_range(0.1..0.6);
+            let mut board = BitBoard::new(width, height);
+            for row in 0..height {
+                for col in 0..width {
+                    board.set(row, col, rng.gen_bool(density));
+                }
+            }
+            let rule = rules[case % rules.len()].parse()?;
+            let topology = topologies[case % topologies.len()];
+            let mut serial = ConwayGol::from_board(board.clone())
+                .with_rule(rule)
+                .with_topology(topology);
+            let mut parallel = ConwayGol::from_board(board)
+                .with_rule(rule)
+                .with_topology(topology);
+            for _ in 0..6 {
+                serial.step(false);
+                parallel.tick_par();
+                assert_eq!(
+                    serial.board, parallel.board,
+                    "{width}x{height}, {topology:?}"
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Skipping words whose neighborhoods didn't change gives the same boards
+    /// as recomputing every word, even with edits between ticks
+    #[test]
+    fn tracked_tick_matches_full() -> anyhow::Result<()> {
+        let mut rng = StdRng::seed_from_u64(2012);
+        let topologies = [
+            Topology::Bounded,
+            Topology::AliveEdge,
+            Topology::Torus,
+            Topology::KleinBottle,
+            Topology::CrossSurface,
+        ];
+        let rules = ["B3/S23", "B36/S23", "B2/S", "B0123478/S01234678"];
+        for case in 0..40 {
+            let (width, height) = (rng.gen_range(1..200), rng.gen_range(1..60));
+            let mut board = BitBoard::new(width, height);
+            // A small soup leaves most of the board still
+            for _ in 0..(width * height / 8) {
+                let (row, col) = (rng.gen_range(0..height), rng.gen_range(0..width.min(20)));
+                board.set(row, col, true);
+            }
+            let rule = rules[case % rules.len()].parse()?;
+            let topology = topologies[case % topologies.len()];
+            let mut full = ConwayGol::from_board(board.clone())
+                .with_rule(rule)
+                .with_topology(topology);
+            let mut tracked = ConwayGol::from_board(board)
+                .with_rule(rule)
+                .with_topology(topology);
+            for gen in 0..30 {
+                if gen % 7 == 3 {
+                    let (row, col) = (rng.gen_range(0..height), rng.gen_range(0..width));
+                    let is_alive = rng.gen_bool(0.5);
+                    full.set(row as i64, col as i64, is_alive);
+                    tracked.set(row as i64, col as i64, is_alive);
+                }
+                let before = tracked.board.clone();
+                full.stale = true;
+                full.tick();
+                tracked.tick();
+                assert_eq!(
+                    tracked.board, full.board,
+                    "{width}x{height}, {rule}, {topology:?}"
+                );
+
+                let mut flipped = BitBoard::new(width, height);
+                for (row, col) in before.iter_alive().chain(tracked.board.iter_alive()) {
+                    flipped.set(
+                        row,
+                        col,
+                        before.get(row, col) != tracked.board.get(row, col),
+                    );
+                }
+                assert_eq!(tracked.changed, flipped);
+            }
+        }
+        Ok(())
+    }
+
+    /// Only the cells that flipped are reported as changed
+    #[test]
+    fn changed_cells() -> anyhow::Result<()> {
+        let mut board = BitBoard::new(10, 10);
+        // A blinker next to a block
+        for (row, col) in [(2, 1), (2, 2), (2, 3), (6, 6), (6, 7), (7, 6), (7, 7)] {
+            board.set(row, col, true);
+        }
+        let mut cw = ConwayGol::from_board(board);
+        for _ in 0..3 {
+            cw.tick();
+            let flipped: Vec<_> = cw.changed().iter_alive().collect();
+            assert_eq!(flipped.len(), 4);
+            assert!(flipped.contains(&(2, 1)) && flipped.contains(&(3, 2)));
+        }
+
+        cw.set(0, 9, true);
+        cw.set(6, 6, true);
+        assert!(cw.changed().get(0, 9));
+        assert!(!cw.changed().get(6, 6));
+        cw.tick();
+        assert!(cw.changed().get(0, 9));
+        assert!(!cw.board().get(0, 9));
+        Ok(())
+    }
+
+    /// A cell that flipped on the last tick and is edited back is still
+    /// recomputed on the next one, including through undo and redo
+    #[test]
+    fn edited_back_cells_tick() {
+        let mut board = BitBoard::new(10, 10);
+        board.set(5, 5, true);
+        let mut cw = ConwayGol::from_board(board);
+        cw.tick();
+        assert!(!cw.board().get(5, 5));
+        cw.set(5, 5, true);
+        cw.tick();
+        assert!(!cw.board().get(5, 5));
+
+        cw.begin_edit();
+        cw.set(5, 5, true);
+        let edit = cw.end_edit().expect("Setting a dead cell flips it");
+        cw.tick();
+        cw.redo_edit(&edit);
+        cw.tick();
+        assert!(!cw.board().get(5, 5));
+        cw.set(5, 5, true);
+        cw.tick();
+        cw.undo_edit(&edit);
+        cw.redo_edit(&edit);
+        cw.tick();
+        assert_eq!(cw.board().population(), 0);
+    }
+
+    /// Stepping back returns to exactly the earlier boards, including edits
+    /// made before the tick being undone
+    #[test]
+    fn step_back_and_seek() -> anyhow::Result<()> {
+        let mut rng = StdRng::seed_from_u64(2013);
+        let mut board = BitBoard::new(90, 40);
+        for row in 0..40 {
+            for col in 0..90 {
+                board.set(row, col, rng.gen_bool(0.35));
+            }
+        }
+        let mut cw = ConwayGol::from_board(board.clone())
+            .with_topology(Topology::Torus)
+            .with_history(50);
+        let mut boards = vec![board];
+        for gen in 1..=80 {
+            if gen % 9 == 0 {
+                cw.set(rng.gen_range(0..40), rng.gen_range(0..90), true);
+                *boards.last_mut().unwrap() = cw.board.clone();
+            }
+            cw.tick();
+            boards.push(cw.board.clone());
+        }
+        assert_eq!(cw.generation(), 80);
+        assert_eq!(cw.history().len(), 50);
+
+        for gen in (70..80).rev() {
+            assert!(cw.step_back());
+            assert_eq!(cw.generation(), gen);
+            assert_eq!(cw.board, boards[gen as usize]);
+        }
+        // Edits to the present are folded into the tick before it
+        cw.set(0, 0, !cw.board.get(0, 0));
+        assert!(cw.step_back());
+        assert_eq!(cw.board, boards[69]);
+
+        cw.seek(40)?;
+        assert_eq!(cw.board, boards[40]);
+        // Seeking forward ticks again, which only matches up to the next edit
+        cw.seek(43)?;
+        assert_eq!(cw.board, boards[43]);
+        assert!(cw.seek(29).is_err());
+        cw.seek(30)?;
+        assert_eq!(cw.board, boards[30]);
+        assert_eq!(cw.history().len(), 0);
+        assert!(!cw.step_back());
+        Ok(())
+    }
+
+    /// Ages count generations since each cell last flipped, whether by a
+    /// tick, an edit, or stepping back
+    #[test]
+    fn cell_ages() {
+        let mut board = BitBoard::new(8, 8);
+        // A blinker next to a block
+        for (row, col) in [(1, 1), (1, 2), (1, 3), (5, 5), (5, 6), (6, 5), (6, 6)] {
+            board.set(row, col, true);
+        }
+        let mut cw = ConwayGol::from_board(board);
+        for _ in 0..3 {
+            cw.tick();
+        }
+        // The blinker's center and the block never flip, while its ends were
+        // just born again
+        assert_eq!((cw.age(1, 2), cw.age(5, 5)), (3, 3));
+        assert_eq!((cw.age(0, 2), cw.age(2, 2)), (0, 0));
+        assert_eq!((cw.age(1, 1), cw.board().get(1, 1)), (0, false));
+        assert_eq!(cw.age(7, 0), 3);
+
+        cw.tick();
+        cw.set(7, 0, true);
+        assert_eq!((cw.age(1, 1), cw.age(0, 2), cw.age(7, 0)), (0, 0, 0));
+        cw.tick();
+        assert_eq!(cw.age(7, 0), 0);
+        assert!(!cw.board().get(7, 0));
+        assert!(cw.step_back());
+        assert_eq!((cw.age(7, 0), cw.age(1, 1), cw.age(5, 6)), (0, 0, 4));
+    }
+
+    /// Reseeding starts a new run whose flips are all marked as changed
+    #[test]
+    fn reseed_and_hash() -> anyhow::Result<()> {
+        let soup = Soup::new(14).with_region(30, 20);
+        let mut cw = ConwayGol::build_soup(40, 30, &soup)?;
+        let start = cw.board.clone();
+        let start_hash = cw.board_hash();
+        for _ in 0..5 {
+            cw.tick();
+        }
+        assert_ne!(cw.board_hash(), start_hash);
+
+        let before = cw.board.clone();
+        cw.reseed(&soup)?;
+        assert_eq!((cw.generation(), cw.history().len()), (0, 0));
+        assert_eq!(cw.board, start);
+        assert_eq!(cw.board_hash(), start_hash);
+        for row in 0..30 {
+            for col in 0..40 {
+                let flipped = before.get(row, col) != start.get(row, col);
+                assert_eq!(cw.changed().get(row, col), flipped);
+            }
+        }
+        assert!(cw.reseed(&soup.with_region(50, 20)).is_err());
+        Ok(())
+    }
+
+    /// Gliders heading in every diagonal direction wrap back to where they
+    /// started on a torus
+    #[test]
+    fn gliders_cross_torus_edges() {
+        let dim = 16;
+        for (d_row, d_col) in [(1, 1), (1, -1), (-1, 1), (-1, -1)] {
+            let mut board = BitBoard::new(dim, dim);
+            glider(&mut board, 2, 9, d_row, d_col);
+            let start = board.clone();
+            let mut cw = ConwayGol::from_board(board).with_topology(Topology::Torus);
+            for _ in 0..(4 * dim) {
+                cw.tick();
+                assert_eq!(cw.board.population(), 5);
+            }
+            assert_eq!(cw.board, start, "direction {d_row}, {d_col}");
+        }
+    }
+
+    /// A spaceship races down a wide strip and a glider climbs a tall board,
+    /// both wrapping back to where they started
+    #[test]
+    fn ships_cross_rectangular_torus() {
+        let mut strip = BitBoard::new(80, 12);
+        let lwss = [
+            (0, 1),
+            (0, 4),
+            (1, 0),
+            (2, 0),
+            (2, 4),
+            (3, 0),
+            (3, 1),
+            (3, 2),
+            (3, 3),
+        ];
+        for (row, col) in lwss {
+            strip.set(row + 4, col + 40, true);
+        }
+        let mut tall = BitBoard::new(12, 60);
+        glider(&mut tall, 30, 4, -1, 1);
+
+        // The LWSS moves c/2 across 80 columns, and the glider needs a number
+        // of diagonal steps that both 12 and 60 divide
+        for (board, generations) in [(strip, 160), (tall, 240)] {
+            let mut cw = ConwayGol::from_board(board.clone()).with_topology(Topology::Torus);
+            for gen in 1..=generations {
+                cw.tick();
+                if gen % 4 == 0 && gen < generations {
+                    assert_ne!(cw.board, board);
+                }
+            }
+            assert_eq!(cw.board, board);
+        }
+    }
+
+    /// Gliders survive every edge crossing on the non-orientable surfaces, and
+    /// return to their starting cells once each flip has been undone. Their
+    /// paths avoid the singular corners of the cross-surface.
+    #[test]
+    fn gliders_cross_twisted_edges() {
+        let dim = 16;
+        for topology in [Topology::KleinBottle, Topology::CrossSurface] {
+            for (d_row, d_col) in [(1, 1), (1, -1), (-1, 1), (-1, -1)] {
+                let mut board = BitBoard::new(dim, dim);
+                glider(&mut board, 2, 7, d_row, d_col);
+                let start = board.clone();
+                let mut cw = ConwayGol::from_board(board).with_topology(topology);
+                for _ in 0..(8 * dim) {
+                    cw.tick();
+                    assert_eq!(cw.board.population(), 5, "{topology:?}");
+                }
+                assert_eq!(cw.board, start, "{topology:?} direction {d_row}, {d_col}");
+            }
+        }
+    }
+
+    /// A glider is destroyed by a dead edge instead of wrapping around
+    #[test]
+    fn glider_dies_at_bounded_edge() {
+        let dim = 16;
+        let mut board = BitBoard::new(dim, dim);
+        glider(&mut board, 2, 9, 1, 1);
+        let start = board.clone();
+        let mut cw = ConwayGol::from_board(board);
+        for _ in 0..(4 * dim) {
+            cw.tick();
+        }
+        assert_ne!(cw.board, start);
+        assert_ne!(cw.board.population(), 5);
     }
 }
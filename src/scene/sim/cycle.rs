@@ -0,0 +1,237 @@
+use super::ConwayGol;
+use bevy::ecs::component::Component;
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+};
+
+/// Longest period the detector notices by default
+pub const DEFAULT_MAX_PERIOD: usize = 256;
+
+/// How a board has settled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Settled {
+    /// Every cell died by this generation
+    Dead { generation: u64 },
+    /// The board at `first_repeat` matches the board `period` generations
+    /// earlier, so it will keep cycling from here on
+    Periodic { period: u64, first_repeat: u64 },
+}
+
+impl fmt::Display for Settled {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Settled::Dead { generation } => write!(f, "died out by generation {generation}"),
+            Settled::Periodic {
+                period: 1,
+                first_repeat,
+            } => write!(f, "stable since generation {}", first_repeat - 1),
+            Settled::Periodic {
+                period,
+                first_repeat,
+            } => write!(
+                f,
+                "period {period} since generation {}",
+                first_repeat - period
+            ),
+        }
+    }
+}
+
+/// Watches a board tick by tick and reports when it dies out or starts
+/// repeating itself. Boards are compared by hash, so a vanishingly unlikely
+/// hash collision could be reported as a cycle.
+#[derive(Debug, Clone, Component)]
+pub struct CycleDetector {
+    /// Generation each recent board hash was last seen at
+    seen: HashMap<u64, u64>,
+    /// Recent (generation, hash) pairs, oldest first
+    recent: VecDeque<(u64, u64)>,
+    max_period: usize,
+    /// What was last reported, until the board leaves that state
+    settled: Option<Settled>,
+}
+
+impl Default for CycleDetector {
+    fn default() -> Self {
+        CycleDetector::new(DEFAULT_MAX_PERIOD)
+    }
+}
+
+impl CycleDetector {
+    /// Returns a detector that notices cycles up to `max_period` generations.
+    pub fn new(max_period: usize) -> Self {
+        CycleDetector {
+            seen: HashMap::new(),
+            recent: VecDeque::new(),
+            max_period,
+            settled: None,
+        }
+    }
+
+    /// Forgets every board seen so far.
+    pub fn reset(&mut self) {
+        self.seen.clear();
+        self.recent.clear();
+        self.settled = None;
+    }
+
+    /// Looks at the board's current generation. Returns how the board has
+    /// settled the first time it's noticed, and None otherwise.
+    pub fn observe(&mut self, gol: &ConwayGol) -> Option<Settled> {
+        let generation = gol.generation();
+        if self
+            .recent
+            .back()
+            .is_some_and(|(last, _)| *last >= generation)
+        {
+            // The board went back in time, so what came after is invalid
+            self.reset();
+        }
+
+        let hash = gol.board_hash();
+        let outcome = if gol.board().population() == 0 {
+            Some(Settled::Dead { generation })
+        } else {
+            self.seen.get(&hash).map(|earlier| Settled::Periodic {
+                period: generation - earlier,
+                first_repeat: generation,
+            })
+        };
+
+        self.seen.insert(hash, generation);
+        self.recent.push_back((generation, hash));
+        while self.recent.len() > self.max_period {
+            let (old_gen, old_hash) = self.recent.pop_front().expect("Recent is non-empty");
+            if self.seen.get(&old_hash) == Some(&old_gen) {
+                self.seen.remove(&old_hash);
+            }
+        }
+
+        let newly_settled = match (self.settled, outcome) {
+            (_, None) => None,
+            (Some(Settled::Dead { .. }), Some(Settled::Dead { .. })) => None,
+            (Some(Settled::Periodic { period, .. }), Some(Settled::Periodic { period: p, .. }))
+                if period == p =>
+            {
+                None
+            }
+            (_, Some(outcome)) => Some(outcome),
+        };
+        if outcome.is_none() || newly_settled.is_some() {
+            self.settled = outcome;
+        }
+        newly_settled
+    }
+}
+
+#[cfg(test)]
+mod cycle_tests {
+    use super::{CycleDetector, Settled};
+    use crate::scene::{
+        bitboard::BitBoard,
+        sim::{ConwayGol, Universe},
+        topology::Topology,
+    };
+
+    /// Ticks until the detector reports something or the generation limit
+    fn run(cw: &mut ConwayGol, detector: &mut CycleDetector, limit: u64) -> Option<Settled> {
+        while cw.generation() < limit {
+            if let Some(settled) = detector.observe(cw) {
+                return Some(settled);
+            }
+            cw.tick();
+        }
+        None
+    }
+
+    fn board_with(width: usize, height: usize, cells: &[(usize, usize)]) -> BitBoard {
+        let mut board = BitBoard::new(width, height);
+        for (row, col) in cells {
+            board.set(*row, *col, true);
+        }
+        board
+    }
+
+    #[test]
+    fn still_lifes_and_oscillators() {
+        let mut detector = CycleDetector::default();
+        let block = board_with(8, 8, &[(3, 3), (3, 4), (4, 3), (4, 4)]);
+        let mut cw = ConwayGol::from_board(block);
+        let outcome = run(&mut cw, &mut detector, 10);
+        assert_eq!(
+            outcome,
+            Some(Settled::Periodic {
+                period: 1,
+                first_repeat: 1
+            })
+        );
+        assert_eq!(outcome.unwrap().to_string(), "stable since generation 0");
+        // Staying still isn't news
+        cw.tick();
+        assert_eq!(detector.observe(&cw), None);
+
+        let blinker = board_with(8, 8, &[(3, 2), (3, 3), (3, 4)]);
+        let mut cw = ConwayGol::from_board(blinker);
+        let mut detector = CycleDetector::default();
+        let outcome = run(&mut cw, &mut detector, 10);
+        assert_eq!(
+            outcome,
+            Some(Settled::Periodic {
+                period: 2,
+                first_repeat: 2
+            })
+        );
+        assert_eq!(outcome.unwrap().to_string(), "period 2 since generation 0");
+    }
+
+    /// A glider on a torus returns to its starting cells after crossing the
+    /// board, which only counts as a cycle if the period fits the detector
+    #[test]
+    fn glider_on_torus() {
+        let glider = board_with(12, 12, &[(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+        let mut cw = ConwayGol::from_board(glider.clone()).with_topology(Topology::Torus);
+        assert_eq!(run(&mut cw, &mut CycleDetector::new(40), 200), None);
+
+        let mut cw = ConwayGol::from_board(glider).with_topology(Topology::Torus);
+        let outcome = run(&mut cw, &mut CycleDetector::new(48), 200);
+        assert_eq!(
+            outcome,
+            Some(Settled::Periodic {
+                period: 48,
+                first_repeat: 48
+            })
+        );
+    }
+
+    #[test]
+    fn dies_out() {
+        // A diagonal of three cells dies in two generations
+        let mut cw = ConwayGol::from_board(board_with(8, 8, &[(1, 1), (2, 2), (3, 3), (6, 0)]));
+        let mut detector = CycleDetector::default();
+        assert_eq!(
+            run(&mut cw, &mut detector, 10),
+            Some(Settled::Dead { generation: 2 })
+        );
+        cw.tick();
+        assert_eq!(detector.observe(&cw), None);
+
+        // Stepping back forgets what was seen after
+        cw.step_back();
+        cw.step_back();
+        assert_eq!(detector.observe(&cw), None);
+        cw.set(0, 0, true);
+        cw.set(0, 1, true);
+        cw.set(1, 0, true);
+        for _ in 0..2 {
+            // The tromino takes a generation to fill into a block
+            cw.tick();
+            assert_eq!(detector.observe(&cw), None);
+        }
+        cw.tick();
+        assert!(matches!(
+            detector.observe(&cw),
+            Some(Settled::Periodic { period: 1, .. })
+        ));
+    }
+}
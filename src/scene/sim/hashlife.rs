@@ -0,0 +1,804 @@
+use super::{Bounds, Universe};
+use crate::scene::{bitboard::BitBoard, rule::Rule};
+use anyhow::{bail, Context};
+use bevy::{ecs::component::Component, log::error};
+use std::collections::HashMap;
+
+/// Index of a node in the HashLife node arena
+pub(crate) type NodeId = u32;
+
+/// The dead and alive leaf nodes, which are single cells
+const DEAD: NodeId = 0;
+const ALIVE: NodeId = 1;
+
+/// Deepest tree the engine will build. Keeps every coordinate inside an i64.
+const MAX_LEVEL: u8 = 60;
+
+/// Largest k that `HashLife::step_pow2` jumps 2^k generations for, since
+/// a jump needs a root k + 3 levels deep
+pub const MAX_STEP_POW2: u8 = MAX_LEVEL - 3;
+
+/// Nodes the arena holds before unreachable ones are first collected
+const COLLECT_THRESHOLD: usize = 1 << 20;
+
+/// A square region of 2^level x 2^level cells made of four quadrants one
+/// level down. Identical regions share a single node.
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    /// nw, ne, sw, se
+    children: [NodeId; 4],
+    level: u8,
+    population: u64,
+}
+
+/// A Game of Life engine that memoizes the future of every distinct square
+/// region it sees in a quadtree, so repetitive patterns can be advanced by
+/// billions of generations at a time.
+/// https://en.wikipedia.org/wiki/Hashlife
+#[derive(Debug, Component)]
+pub struct HashLife {
+    nodes: Vec<Node>,
+    /// Finds the existing node with a given set of children
+    index: HashMap<[NodeId; 4], NodeId>,
+    /// The center of a node advanced by 2^k generations, keyed by node and k
+    results: HashMap<(NodeId, u8), NodeId>,
+    /// An all-dead node for each level
+    empty: Vec<NodeId>,
+    root: NodeId,
+    /// Universe row, col of the root's top left cell
+    origin: (i64, i64),
+    generation: u64,
+    rule: Rule,
+    /// Size of the arena at which the next jump collects garbage first
+    collect_at: usize,
+}
+
+impl Default for HashLife {
+    fn default() -> Self {
+        let leaf = |population| Node {
+            children: [DEAD; 4],
+            level: 0,
+            population,
+        };
+        let mut hl = HashLife {
+            nodes: vec![leaf(0), leaf(1)],
+            index: HashMap::new(),
+            results: HashMap::new(),
+            empty: vec![DEAD],
+            root: DEAD,
+            origin: (0, 0),
+            generation: 0,
+            rule: Rule::default(),
+            collect_at: COLLECT_THRESHOLD,
+        };
+        hl.root = hl.empty(3).expect("A new arena has room for empty nodes");
+        hl.origin = (-4, -4);
+        hl
+    }
+}
+
+impl HashLife {
+    /// Returns an empty universe following B3/S23.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Seeds a universe with the live cells of a board, placing the board's
+    /// top left cell at top, left.
+    pub fn from_board(board: &BitBoard, top: i64, left: i64) -> Self {
+        let mut hl = Self::new();
+        for (row, col) in board.iter_alive() {
+            hl.set(top + row as i64, left + col as i64, true);
+        }
+        hl
+    }
+
+    /// Replaces the rule the universe evolves under, which is B3/S23 by default.
+    /// Errors if the rule has B0, which would fill the infinite plane in one tick.
+    pub fn with_rule(mut self, rule: Rule) -> anyhow::Result<Self> {
+        if rule.births(0) {
+            bail!("Rule {rule} has B0, which an unbounded universe can't simulate");
+        }
+        self.rule = rule;
+        self.results.clear();
+        Ok(self)
+    }
+
+    #[inline]
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    /// Number of generations the universe has been advanced.
+    #[inline]
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Overrides the generation counter, as when loading a saved pattern
+    /// that was already partway through its run.
+    pub fn with_generation(mut self, generation: u64) -> Self {
+        self.generation = generation;
+        self
+    }
+
+    /// Moves the pattern so the middle of its live cells sits at row, col.
+    pub fn centered_on(mut self, row: i64, col: i64) -> Self {
+        if let Some(bounds) = self.bounds() {
+            let middle = (
+                bounds.top + (bounds.bottom - bounds.top) / 2,
+                bounds.left + (bounds.right - bounds.left) / 2,
+            );
+            self.origin = (
+                self.origin.0 + row - middle.0,
+                self.origin.1 + col - middle.1,
+            );
+        }
+        self
+    }
+
+    /// Advances the universe by 2^k generations, first collecting unreachable
+    /// nodes if the arena has grown large.
+    /// Errors if k is greater than `MAX_STEP_POW2`, if the generation
+    /// counter would overflow, or if the pattern grows past the largest
+    /// supported tree or arena, in which case the universe is left as it was.
+    pub fn step_pow2(&mut self, k: u8) -> anyhow::Result<()> {
+        if k > MAX_STEP_POW2 {
+            bail!("HashLife jumps at most 2^{MAX_STEP_POW2} generations at once, not 2^{k}");
+        }
+        let Some(generation) = self.generation.checked_add(1 << k) else {
+            bail!(
+                "Generation {} is too late to advance 2^{k} more",
+                self.generation
+            );
+        };
+        if self.nodes.len() >= self.collect_at {
+            self.collect_garbage();
+        }
+        // Once the pattern fits in the middle quarter of a root at least k + 3
+        // levels deep, nothing can escape the root's center within 2^k ticks.
+        loop {
+            if self.nodes[self.root as usize].level >= k + 3 {
+                let inner = self.center(self.root)?;
+                let inner = self.center(inner)?;
+                if self.node_population(inner) == self.node_population(self.root) {
+                    break;
+                }
+            }
+            self.expand()?;
+        }
+        let level = self.nodes[self.root as usize].level;
+        let quarter = 1i64 << (level - 2);
+        let next = self.successor(self.root, k)?;
+        self.set_root(next, (self.origin.0 + quarter, self.origin.1 + quarter))?;
+        self.generation = generation;
+        Ok(())
+    }
+
+    /// Advances the universe by any number of generations, using the binary
+    /// expansion of `generations` to take as few memoized jumps as possible.
+    /// Errors without advancing if `generations` needs a jump longer than
+    /// 2^`MAX_STEP_POW2` or the generation counter would overflow, and stops
+    /// at the last jump that fit if the pattern outgrows the universe.
+    pub fn advance(&mut self, generations: u64) -> anyhow::Result<()> {
+        if generations >> MAX_STEP_POW2 >> 1 != 0 {
+            bail!(
+                "HashLife advances fewer than 2^{} generations at once, not {generations}",
+                MAX_STEP_POW2 + 1
+            );
+        }
+        if self.generation.checked_add(generations).is_none() {
+            bail!(
+                "Generation {} is too late to advance {generations} more",
+                self.generation
+            );
+        }
+        for k in 0..=MAX_STEP_POW2 {
+            if generations >> k & 1 == 1 {
+                self.step_pow2(k)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The leaf node for a single cell.
+    #[inline]
+    pub(crate) fn leaf(is_alive: bool) -> NodeId {
+        if is_alive {
+            ALIVE
+        } else {
+            DEAD
+        }
+    }
+
+    /// Width of a node is 2^level cells.
+    #[inline]
+    pub(crate) fn node_level(&self, node: NodeId) -> u8 {
+        self.nodes[node as usize].level
+    }
+
+    #[inline]
+    pub(crate) fn root(&self) -> NodeId {
+        self.root
+    }
+
+    /// Replaces the whole universe with a tree whose top left cell is at
+    /// row, col of `origin`.
+    pub(crate) fn set_root(&mut self, root: NodeId, origin: (i64, i64)) -> anyhow::Result<()> {
+        self.root = root;
+        self.origin = origin;
+        while self.node_level(self.root) < 3 {
+            self.expand()?;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    pub(crate) fn node_population(&self, node: NodeId) -> u64 {
+        self.nodes[node as usize].population
+    }
+
+    #[inline]
+    pub(crate) fn children(&self, node: NodeId) -> [NodeId; 4] {
+        self.nodes[node as usize].children
+    }
+
+    /// Returns the canonical node with the given quadrants.
+    /// Errors if the arena has no ids left for a new node.
+    pub(crate) fn join(
+        &mut self,
+        nw: NodeId,
+        ne: NodeId,
+        sw: NodeId,
+        se: NodeId,
+    ) -> anyhow::Result<NodeId> {
+        let children = [nw, ne, sw, se];
+        if let Some(&node) = self.index.get(&children) {
+            return Ok(node);
+        }
+        let node = Node {
+            children,
+            level: self.nodes[nw as usize].level + 1,
+            population: children
+                .iter()
+                .map(|child| self.node_population(*child))
+                .sum(),
+        };
+        let id = NodeId::try_from(self.nodes.len())
+            .context("HashLife node arena is full, even after collecting garbage")?;
+        self.nodes.push(node);
+        self.index.insert(children, id);
+        Ok(id)
+    }
+
+    /// Returns the all-dead node at a level.
+    pub(crate) fn empty(&mut self, level: u8) -> anyhow::Result<NodeId> {
+        while self.empty.len() <= level as usize {
+            let below = self.empty[self.empty.len() - 1];
+            let node = self.join(below, below, below, below)?;
+            self.empty.push(node);
+        }
+        Ok(self.empty[level as usize])
+    }
+
+    /// Rebuilds the arena from the nodes reachable from the root and the
+    /// empty nodes, and forgets every memoized result. Without this, every
+    /// region the pattern ever passed through would stay in memory.
+    fn collect_garbage(&mut self) {
+        let leaves = vec![self.nodes[DEAD as usize], self.nodes[ALIVE as usize]];
+        let old = std::mem::replace(&mut self.nodes, leaves);
+        self.index.clear();
+        self.results.clear();
+        let mut moved = HashMap::new();
+        self.root = self.keep(self.root, &old, &mut moved);
+        let empty = std::mem::take(&mut self.empty);
+        self.empty = empty
+            .into_iter()
+            .map(|node| self.keep(node, &old, &mut moved))
+            .collect();
+        self.collect_at = (2 * self.nodes.len()).max(COLLECT_THRESHOLD);
+    }
+
+    /// Copies a node from the old arena into the current one after its
+    /// children, returning its new id. Nodes that were already copied are
+    /// reused.
+    fn keep(&mut self, node: NodeId, old: &[Node], moved: &mut HashMap<NodeId, NodeId>) -> NodeId {
+        if node == DEAD || node == ALIVE {
+            return node;
+        }
+        if let Some(&id) = moved.get(&node) {
+            return id;
+        }
+        let mut children = old[node as usize].children;
+        for child in &mut children {
+            *child = self.keep(*child, old, moved);
+        }
+        // The new arena is no larger than the old one, so ids still fit
+        let id = self.nodes.len() as NodeId;
+        self.nodes.push(Node {
+            children,
+            ..old[node as usize]
+        });
+        self.index.insert(children, id);
+        moved.insert(node, id);
+        id
+    }
+
+    /// Doubles the size of the root, keeping the pattern at its center.
+    /// Errors if the root is already as deep as the engine allows or the
+    /// arena is full.
+    fn expand(&mut self) -> anyhow::Result<()> {
+        let level = self.nodes[self.root as usize].level;
+        if level >= MAX_LEVEL {
+            bail!("Pattern outgrew the HashLife universe, which is 2^{MAX_LEVEL} cells wide");
+        }
+        let [nw, ne, sw, se] = self.children(self.root);
+        let e = self.empty(level - 1)?;
+        let nw = self.join(e, e, e, nw)?;
+        let ne = self.join(e, e, ne, e)?;
+        let sw = self.join(e, sw, e, e)?;
+        let se = self.join(se, e, e, e)?;
+        self.root = self.join(nw, ne, sw, se)?;
+        let half = 1i64 << (level - 1);
+        self.origin = (self.origin.0 - half, self.origin.1 - half);
+        Ok(())
+    }
+
+    /// The node one level down centered inside a node.
+    fn center(&mut self, node: NodeId) -> anyhow::Result<NodeId> {
+        let [nw, ne, sw, se] = self.children(node);
+        let (nw, ne, sw, se) = (
+            self.children(nw)[3],
+            self.children(ne)[2],
+            self.children(sw)[1],
+            self.children(se)[0],
+        );
+        self.join(nw, ne, sw, se)
+    }
+
+    /// The node straddling the boundary between two side by side nodes.
+    fn horizontal_center(&mut self, w: NodeId, e: NodeId) -> anyhow::Result<NodeId> {
+        let [_, w_ne, _, w_se] = self.children(w);
+        let [e_nw, _, e_sw, _] = self.children(e);
+        self.join(w_ne, e_nw, w_se, e_sw)
+    }
+
+    /// The node straddling the boundary between two stacked nodes.
+    fn vertical_center(&mut self, n: NodeId, s: NodeId) -> anyhow::Result<NodeId> {
+        let [_, _, n_sw, n_se] = self.children(n);
+        let [s_nw, s_ne, _, _] = self.children(s);
+        self.join(n_sw, n_se, s_nw, s_ne)
+    }
+
+    /// Returns the center of a node at level n advanced by 2^k generations,
+    /// where k <= n - 2.
+    fn successor(&mut self, node: NodeId, k: u8) -> anyhow::Result<NodeId> {
+        let level = self.nodes[node as usize].level;
+        debug_assert!(level >= 2 && k <= level - 2);
+        if self.node_population(node) == 0 {
+            return self.empty(level - 1);
+        }
+        if let Some(&result) = self.results.get(&(node, k)) {
+            return Ok(result);
+        }
+
+        let result = if level == 2 {
+            self.step_leaf_square(node)?
+        } else {
+            // Split the node into a 3x3 grid of overlapping subnodes one level down
+            let [nw, ne, sw, se] = self.children(node);
+            let n = self.horizontal_center(nw, ne)?;
+            let w = self.vertical_center(nw, sw)?;
+            let c = self.center(node)?;
+            let e = self.vertical_center(ne, se)?;
+            let s = self.horizontal_center(sw, se)?;
+            let grid = [nw, n, ne, w, c, e, sw, s, se];
+
+            // At full speed both halves of the jump advance time. Otherwise the
+            // first half only re-centers and the second half does all the work.
+            let full_speed = k == level - 2;
+            let mut parts = [DEAD; 9];
+            for (part, sub) in parts.iter_mut().zip(grid) {
+                *part = if full_speed {
+                    self.successor(sub, k - 1)?
+                } else {
+                    self.center(sub)?
+                };
+            }
+            let second_k = if full_speed { k - 1 } else { k };
+
+            let quads = [[0, 1, 3, 4], [1, 2, 4, 5], [3, 4, 6, 7], [4, 5, 7, 8]];
+            let mut out = [DEAD; 4];
+            for (quad, inds) in out.iter_mut().zip(quads) {
+                let joined = self.join(
+                    parts[inds[0]],
+                    parts[inds[1]],
+                    parts[inds[2]],
+                    parts[inds[3]],
+                )?;
+                *quad = self.successor(joined, second_k)?;
+            }
+            self.join(out[0], out[1], out[2], out[3])?
+        };
+        self.results.insert((node, k), result);
+        Ok(result)
+    }
+
+    /// Advances the center 2x2 cells of a 4x4 node by one generation.
+    fn step_leaf_square(&mut self, node: NodeId) -> anyhow::Result<NodeId> {
+        let mut cells = [[false; 4]; 4];
+        for (quad_ind, quad) in self.children(node).into_iter().enumerate() {
+            for (cell_ind, cell) in self.children(quad).into_iter().enumerate() {
+                let row = (quad_ind / 2) * 2 + cell_ind / 2;
+                let col = (quad_ind % 2) * 2 + cell_ind % 2;
+                cells[row][col] = cell == ALIVE;
+            }
+        }
+
+        let mut next = [DEAD; 4];
+        for (ind, out) in next.iter_mut().enumerate() {
+            let (row, col) = (1 + ind / 2, 1 + ind % 2);
+            let nb_ct = cells[(row - 1)..=(row + 1)]
+                .iter()
+                .flat_map(|nb_row| &nb_row[(col - 1)..=(col + 1)])
+                .filter(|is_alive| **is_alive)
+                .count()
+                - cells[row][col] as usize;
+            if self.rule.next_state(cells[row][col], nb_ct) {
+                *out = ALIVE;
+            }
+        }
+        self.join(next[0], next[1], next[2], next[3])
+    }
+
+    /// Width of the root in cells
+    #[inline]
+    fn root_width(&self) -> i64 {
+        1 << self.nodes[self.root as usize].level
+    }
+
+    /// Whether row, col lies inside the root.
+    fn contains(&self, row: i64, col: i64) -> bool {
+        let (top, left) = self.origin;
+        let width = self.root_width();
+        top <= row && row < top + width && left <= col && col < left + width
+    }
+
+    /// Sets whether the cell at row, col is alive, growing the root to reach it.
+    /// Errors if the cell is out of reach, leaving the cells as they were.
+    fn try_set(&mut self, row: i64, col: i64, is_alive: bool) -> anyhow::Result<()> {
+        while !self.contains(row, col) {
+            self.expand()?;
+        }
+        let (row, col) = (row - self.origin.0, col - self.origin.1);
+        self.root = self.set_in(self.root, row, col, is_alive)?;
+        Ok(())
+    }
+
+    /// Returns a copy of `node` with the cell at row, col relative to the
+    /// node's top left corner set to `is_alive`.
+    fn set_in(
+        &mut self,
+        node: NodeId,
+        row: i64,
+        col: i64,
+        is_alive: bool,
+    ) -> anyhow::Result<NodeId> {
+        let level = self.nodes[node as usize].level;
+        if level == 0 {
+            return Ok(if is_alive { ALIVE } else { DEAD });
+        }
+        let half = 1i64 << (level - 1);
+        let quad = ((row >= half) as usize) * 2 + (col >= half) as usize;
+        let mut children = self.children(node);
+        children[quad] = self.set_in(children[quad], row % half, col % half, is_alive)?;
+        self.join(children[0], children[1], children[2], children[3])
+    }
+
+    /// Copies the live cells of `node`, whose top left cell is at row, col,
+    /// into the part of `board` it overlaps. The board's top left cell is at
+    /// top, left.
+    fn copy_into(
+        &self,
+        node: NodeId,
+        (row, col): (i64, i64),
+        (top, left): (i64, i64),
+        board: &mut BitBoard,
+    ) {
+        let level = self.nodes[node as usize].level;
+        let width = 1i64 << level;
+        let overlaps = row < top + board.height() as i64
+            && top < row + width
+            && col < left + board.width() as i64
+            && left < col + width;
+        if self.node_population(node) == 0 || !overlaps {
+            return;
+        }
+        if level == 0 {
+            board.set((row - top) as usize, (col - left) as usize, true);
+            return;
+        }
+        let half = width / 2;
+        for (ind, child) in self.children(node).into_iter().enumerate() {
+            let child_pos = (row + half * (ind / 2) as i64, col + half * (ind % 2) as i64);
+            self.copy_into(child, child_pos, (top, left), board);
+        }
+    }
+
+    /// Finds the smallest offset of a live cell along one axis within a node,
+    /// memoized per node. `near` and `far` pick which quadrants lie nearer
+    /// and farther along that axis.
+    fn min_offset(
+        &self,
+        node: NodeId,
+        near: [usize; 2],
+        far: [usize; 2],
+        memo: &mut HashMap<NodeId, Option<i64>>,
+    ) -> Option<i64> {
+        if self.node_population(node) == 0 {
+            return None;
+        }
+        let level = self.nodes[node as usize].level;
+        if level == 0 {
+            return Some(0);
+        }
+        if let Some(&offset) = memo.get(&node) {
+            return offset;
+        }
+        let children = self.children(node);
+        let half = 1i64 << (level - 1);
+        let nearest = |quads: [usize; 2], memo: &mut HashMap<NodeId, Option<i64>>| {
+            quads
+                .iter()
+                .filter_map(|quad| self.min_offset(children[*quad], near, far, memo))
+                .min()
+        };
+        let offset = nearest(near, memo).or_else(|| nearest(far, memo).map(|off| off + half));
+        memo.insert(node, offset);
+        offset
+    }
+}
+
+impl Universe for HashLife {
+    /// Logs an error and stays put if the pattern reaches the edge of the
+    /// largest supported tree, 2^59 cells from its center.
+    fn tick(&mut self) {
+        if let Err(err) = self.step_pow2(0) {
+            error!("{err:#}");
+        }
+    }
+
+    fn is_alive(&self, row: i64, col: i64) -> bool {
+        if !self.contains(row, col) {
+            return false;
+        }
+        let (mut row, mut col) = (row - self.origin.0, col - self.origin.1);
+        let mut node = self.root;
+        loop {
+            let level = self.nodes[node as usize].level;
+            if level == 0 || self.node_population(node) == 0 {
+                return node == ALIVE;
+            }
+            let half = 1i64 << (level - 1);
+            let quad = ((row >= half) as usize) * 2 + (col >= half) as usize;
+            node = self.children(node)[quad];
+            row %= half;
+            col %= half;
+        }
+    }
+
+    /// Logs an error and does nothing if the cell is farther than 2^59
+    /// cells from the origin.
+    fn set(&mut self, row: i64, col: i64, is_alive: bool) {
+        if let Err(err) = self.try_set(row, col, is_alive) {
+            error!("{err:#}");
+        }
+    }
+
+    fn population(&self) -> usize {
+        self.node_population(self.root) as usize
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        const NW: usize = 0;
+        const NE: usize = 1;
+        const SW: usize = 2;
+        const SE: usize = 3;
+        let width = self.root_width();
+        let (top, left) = self.origin;
+        let offset = |near, far| self.min_offset(self.root, near, far, &mut HashMap::new());
+        Some(Bounds {
+            top: top + offset([NW, NE], [SW, SE])?,
+            left: left + offset([NW, SW], [NE, SE])?,
+            // Measuring from the far side mirrors the offset
+            bottom: top + width - 1 - offset([SW, SE], [NW, NE])?,
+            right: left + width - 1 - offset([NE, SE], [NW, SW])?,
+        })
+    }
+
+    fn window(&self, top: i64, left: i64, width: usize, height: usize) -> BitBoard {
+        let mut board = BitBoard::new(width, height);
+        self.copy_into(self.root, self.origin, (top, left), &mut board);
+        board
+    }
+}
+
+#[cfg(test)]
+mod hashlife_tests {
+    use super::{HashLife, MAX_STEP_POW2};
+    use crate::scene::{
+        bitboard::BitBoard,
+        sim::{Bounds, Universe},
+        sparse::SparseGol,
+    };
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    fn place(universe: &mut impl Universe, cells: &[(i64, i64)]) {
+        for (row, col) in cells {
+            universe.set(*row, *col, true);
+        }
+    }
+
+    const GLIDER: [(i64, i64); 5] = [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)];
+    const R_PENTOMINO: [(i64, i64); 5] = [(0, 1), (0, 2), (1, 0), (1, 1), (2, 1)];
+
+    #[test]
+    fn set_and_query() {
+        let mut hl = HashLife::new();
+        place(&mut hl, &[(0, 0), (-100, 7), (3, -2000)]);
+        assert!(hl.is_alive(-100, 7));
+        assert!(!hl.is_alive(-100, 8));
+        assert_eq!(hl.population(), 3);
+        assert_eq!(
+            hl.bounds(),
+            Some(Bounds {
+                top: -100,
+                left: -2000,
+                bottom: 3,
+                right: 7
+            })
+        );
+        hl.set(3, -2000, false);
+        assert_eq!(hl.population(), 2);
+    }
+
+    /// Matches the sparse backend generation by generation and in big jumps
+    #[test]
+    fn matches_sparse_backend() -> anyhow::Result<()> {
+        let mut rng = StdRng::seed_from_u64(5);
+        let mut soup = BitBoard::new(16, 16);
+        for row in 0..16 {
+            for col in 0..16 {
+                soup.set(row, col, rng.gen_bool(0.5));
+            }
+        }
+        let rule = "B36/S23".parse()?;
+        let mut sparse = SparseGol::from_board(&soup, -8, 3).with_rule(rule)?;
+        let mut single = HashLife::from_board(&soup, -8, 3).with_rule(rule)?;
+        let mut jumps = HashLife::from_board(&soup, -8, 3).with_rule(rule)?;
+
+        for gen in 1..=128 {
+            sparse.tick();
+            single.tick();
+            let bounds = sparse.bounds().expect("Soup shouldn't die out this early");
+            let window = |u: &dyn Universe| {
+                u.window(bounds.top, bounds.left, bounds.width(), bounds.height())
+            };
+            assert_eq!(window(&single), window(&sparse), "generation {gen}");
+            assert_eq!(single.bounds(), sparse.bounds());
+            if gen % 32 == 0 {
+                jumps.step_pow2(5)?;
+                assert_eq!(jumps.generation(), gen);
+                assert_eq!(window(&jumps), window(&sparse), "generation {gen}");
+            }
+        }
+        Ok(())
+    }
+
+    /// A glider advanced a billion generations is a billion / 4 cells away
+    #[test]
+    fn glider_at_one_billion() -> anyhow::Result<()> {
+        let mut hl = HashLife::new();
+        place(&mut hl, &GLIDER);
+        hl.advance(1_000_000_000)?;
+        assert_eq!(hl.generation(), 1_000_000_000);
+        assert_eq!(hl.population(), 5);
+        let dist = 250_000_000;
+        assert_eq!(
+            hl.bounds(),
+            Some(Bounds {
+                top: dist,
+                left: dist,
+                bottom: dist + 2,
+                right: dist + 2
+            })
+        );
+        let mut expected = BitBoard::new(3, 3);
+        for (row, col) in GLIDER {
+            expected.set(row as usize, col as usize, true);
+        }
+        assert_eq!(hl.window(dist, dist, 3, 3), expected);
+        Ok(())
+    }
+
+    /// The R-pentomino settles at generation 1103 with 116 cells
+    #[test]
+    fn r_pentomino_settles() -> anyhow::Result<()> {
+        let mut hl = HashLife::new();
+        place(&mut hl, &R_PENTOMINO);
+        hl.advance(1103)?;
+        assert_eq!(hl.population(), 116);
+        hl.step_pow2(16)?;
+        assert_eq!(hl.population(), 116);
+        Ok(())
+    }
+
+    /// Jumps too long for the universe are refused instead of panicking
+    #[test]
+    fn rejects_long_jumps() -> anyhow::Result<()> {
+        let mut hl = HashLife::new();
+        place(&mut hl, &GLIDER);
+        assert!(hl.advance(u64::MAX).is_err());
+        assert!(hl.step_pow2(MAX_STEP_POW2 + 1).is_err());
+        assert_eq!(hl.generation(), 0);
+
+        hl.step_pow2(MAX_STEP_POW2)?;
+        assert_eq!(hl.generation(), 1 << MAX_STEP_POW2);
+        assert_eq!(hl.population(), 5);
+        let mut late = HashLife::new().with_generation(u64::MAX - 1);
+        assert!(late.advance(2).is_err());
+        assert!(late.step_pow2(1).is_err());
+        Ok(())
+    }
+
+    /// Patterns at the edge of the universe stop instead of panicking
+    #[test]
+    fn stops_at_the_edge() {
+        let edge = (1 << 59) - 8;
+        let mut hl = HashLife::new();
+        let cells = GLIDER.map(|(row, col)| (edge + row, edge + col));
+        place(&mut hl, &cells);
+        hl.tick();
+        assert_eq!(hl.generation(), 0);
+        assert_eq!(hl.population(), 5);
+        assert!(hl.is_alive(edge, edge + 1));
+
+        hl.set(i64::MAX, 0, true);
+        assert_eq!(hl.population(), 5);
+    }
+
+    /// Collecting garbage shrinks the arena without changing the pattern
+    #[test]
+    fn collects_unreachable_nodes() -> anyhow::Result<()> {
+        let mut sparse = SparseGol::new();
+        let mut hl = HashLife::new();
+        place(&mut sparse, &R_PENTOMINO);
+        place(&mut hl, &R_PENTOMINO);
+        for _ in 0..200 {
+            sparse.tick();
+            hl.tick();
+        }
+        let grown = hl.nodes.len();
+        // The next jump collects first
+        hl.collect_at = 0;
+        hl.step_pow2(0)?;
+        sparse.tick();
+        assert!(
+            hl.nodes.len() < grown / 2,
+            "{} of {grown} nodes",
+            hl.nodes.len()
+        );
+        assert!(hl.collect_at > grown);
+
+        for gen in 202..=400 {
+            sparse.tick();
+            hl.tick();
+            let bounds = sparse.bounds().expect("The R-pentomino lives on");
+            let window = |u: &dyn Universe| {
+                u.window(bounds.top, bounds.left, bounds.width(), bounds.height())
+            };
+            assert_eq!(window(&hl), window(&sparse), "generation {gen}");
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,154 @@
+use crate::scene::bitboard::BitBoard;
+use std::collections::VecDeque;
+
+/// Ticks remembered by default
+pub const DEFAULT_HISTORY_DEPTH: usize = 1024;
+
+/// The words of a board that flipped between two generations, as word index
+/// and the XOR of the word's old and new values
+type Delta = Vec<(usize, u64)>;
+
+/// A bounded record of how a board changed on each tick. Only the words that
+/// differ are kept, so boards that have mostly settled are cheap to remember.
+#[derive(Debug, Clone)]
+pub struct History {
+    /// Oldest first
+    deltas: VecDeque<Delta>,
+    depth: usize,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        History::new(DEFAULT_HISTORY_DEPTH)
+    }
+}
+
+impl History {
+    /// Returns an empty history that remembers at most `depth` ticks.
+    pub fn new(depth: usize) -> Self {
+        History {
+            deltas: VecDeque::new(),
+            depth,
+        }
+    }
+
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Number of ticks that can currently be undone.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.deltas.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.deltas.is_empty()
+    }
+
+    /// Remembers a tick from the cells it flipped, forgetting the oldest
+    /// tick once the history is full.
+    pub fn record(&mut self, flipped: &BitBoard) {
+        if self.depth == 0 {
+            return;
+        }
+        if self.deltas.len() == self.depth {
+            self.deltas.pop_front();
+        }
+        let delta = flipped
+            .words()
+            .iter()
+            .enumerate()
+            .filter(|(_, word)| **word != 0)
+            .map(|(ind, word)| (ind, *word))
+            .collect();
+        self.deltas.push_back(delta);
+    }
+
+    /// Folds an edit to the current board into the latest tick, so that
+    /// undoing it restores the board from before that tick.
+    pub fn amend(&mut self, word_ind: usize, flipped: u64) {
+        let Some(delta) = self.deltas.back_mut() else {
+            return;
+        };
+        match delta.binary_search_by_key(&word_ind, |(ind, _)| *ind) {
+            Ok(pos) => {
+                delta[pos].1 ^= flipped;
+                if delta[pos].1 == 0 {
+                    delta.remove(pos);
+                }
+            }
+            Err(pos) => delta.insert(pos, (word_ind, flipped)),
+        }
+    }
+
+    /// Rewinds a board by the latest tick, marking the cells that flip in
+    /// `flipped`. Returns false if there's nothing left to undo.
+    pub fn undo(&mut self, board: &mut BitBoard, flipped: &mut BitBoard) -> bool {
+        let Some(delta) = self.deltas.pop_back() else {
+            return false;
+        };
+        flipped.clear();
+        let (words, flipped) = (board.words_mut(), flipped.words_mut());
+        for (ind, word) in delta {
+            words[ind] ^= word;
+            flipped[ind] = word;
+        }
+        true
+    }
+
+    /// Forgets every tick.
+    pub fn clear(&mut self) {
+        self.deltas.clear();
+    }
+}
+
+#[cfg(test)]
+mod history_tests {
+    use super::History;
+    use crate::scene::bitboard::BitBoard;
+
+    #[test]
+    fn bounded_depth() {
+        let mut history = History::new(3);
+        let mut flipped = BitBoard::new(70, 2);
+        for col in 0..5 {
+            flipped.clear();
+            flipped.set(1, col * 10, true);
+            history.record(&flipped);
+        }
+        assert_eq!(history.len(), 3);
+
+        // Undoing flips the newest ticks back in reverse order
+        let mut board = BitBoard::new(70, 2);
+        let mut undone = BitBoard::new(70, 2);
+        for col in [40, 30, 20] {
+            assert!(history.undo(&mut board, &mut undone));
+            assert_eq!(undone.iter_alive().collect::<Vec<_>>(), vec![(1, col)]);
+        }
+        assert!(!history.undo(&mut board, &mut undone));
+        assert_eq!(board.population(), 3);
+        assert!(board.get(1, 30) && history.is_empty());
+    }
+
+    #[test]
+    fn amend_latest() {
+        let mut history = History::new(2);
+        history.amend(0, 1);
+        assert!(history.is_empty());
+
+        let mut flipped = BitBoard::new(130, 1);
+        flipped.set(0, 0, true);
+        history.record(&flipped);
+        history.amend(0, 1);
+        history.amend(2, 1 << 1);
+
+        let mut board = BitBoard::new(130, 1);
+        assert!(history.undo(&mut board, &mut flipped));
+        assert_eq!(board.iter_alive().collect::<Vec<_>>(), vec![(0, 129)]);
+        assert_eq!(flipped, board);
+        assert!(History::new(0).is_empty());
+    }
+}
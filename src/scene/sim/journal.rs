@@ -0,0 +1,62 @@
+use crate::scene::bitboard::BitBoard;
+
+/// A record of edits made to a board, like toggled cells, stamps, region
+/// edits, and reseeds, that can be undone and redone. Edits are kept apart
+/// from ticks, so undoing one only touches the cells it changed, however
+/// many generations have passed since.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EditCommand {
+    /// Every cell the edit flipped, in order, along with whether it was
+    /// alive before
+    pub(super) flips: Vec<(usize, usize, bool)>,
+    /// The boards on either side, for edits that started the board over
+    pub(super) restarted: Option<Restart>,
+}
+
+/// The whole board on either side of a restart. Ticks since then have moved
+/// on from the cells the restart left, so it's undone and redone by putting
+/// back a whole board rather than flipping cells.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Restart {
+    /// The generation and board before the first restart, and the number of
+    /// flips recorded before it
+    pub(super) before: (u64, BitBoard, usize),
+    /// The generation and board after the last restart, and the number of
+    /// flips recorded before it
+    pub(super) after: (u64, BitBoard, usize),
+}
+
+impl EditCommand {
+    /// Whether the edit changed nothing.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.flips.is_empty() && self.restarted.is_none()
+    }
+
+    /// Number of times the edit flipped a cell.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.flips.len()
+    }
+
+    /// Notes that a cell flipped.
+    pub(super) fn flip(&mut self, row: usize, col: usize, was_alive: bool) {
+        self.flips.push((row, col, was_alive));
+    }
+
+    /// Notes that the board started over at a new generation, keeping the
+    /// board from before the first restart.
+    pub(super) fn restart(&mut self, before: (u64, &BitBoard), after: (u64, &BitBoard)) {
+        let flips = self.flips.len();
+        let after = (after.0, after.1.clone(), flips);
+        match &mut self.restarted {
+            Some(restart) => restart.after = after,
+            None => {
+                self.restarted = Some(Restart {
+                    before: (before.0, before.1.clone(), flips),
+                    after,
+                })
+            }
+        }
+    }
+}
@@ -0,0 +1,259 @@
+use super::bitboard::BitBoard;
+use anyhow::bail;
+use bevy::ecs::component::Component;
+use rand::{
+    distributions::{Bernoulli, Distribution},
+    rngs::StdRng,
+    thread_rng, Rng, SeedableRng,
+};
+use std::{fmt, str::FromStr};
+
+/// Width and height of the soups apgsearch uses, which `Soup::new` defaults to
+const DEFAULT_REGION: usize = 16;
+
+/// Mirror symmetries a soup can be generated with, named like apgsearch's
+/// symmetry options: https://conwaylife.com/wiki/Symmetry
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Symmetry {
+    /// No symmetry
+    #[default]
+    C1,
+    /// Mirrored left to right
+    D2,
+    /// Mirrored left to right and top to bottom
+    D4,
+    /// Mirrored across both axes and both diagonals. Needs a square region.
+    D8,
+}
+
+impl Symmetry {
+    /// Returns the cell whose state row, col copies in a width x height
+    /// region. Cells that are their own representative are drawn at random,
+    /// and every representative comes before its copies in row-major order.
+    fn representative(
+        &self,
+        row: usize,
+        col: usize,
+        width: usize,
+        height: usize,
+    ) -> (usize, usize) {
+        let fold_col = col.min(width - 1 - col);
+        let fold_row = row.min(height - 1 - row);
+        match self {
+            Symmetry::C1 => (row, col),
+            Symmetry::D2 => (row, fold_col),
+            Symmetry::D4 => (fold_row, fold_col),
+            Symmetry::D8 => (fold_row.min(fold_col), fold_row.max(fold_col)),
+        }
+    }
+}
+
+impl FromStr for Symmetry {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.trim().to_ascii_uppercase().as_str() {
+            "C1" => Symmetry::C1,
+            "D2" => Symmetry::D2,
+            "D4" => Symmetry::D4,
+            "D8" => Symmetry::D8,
+            _ => bail!("Unknown symmetry {s:?}, expected one of C1, D2, D4, D8"),
+        })
+    }
+}
+
+impl fmt::Display for Symmetry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+/// Describes a reproducible random soup: the same settings always produce
+/// the same cells, so an interesting run can be shared by its seed.
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+pub struct Soup {
+    seed: u64,
+    density: f64,
+    width: usize,
+    height: usize,
+    symmetry: Symmetry,
+}
+
+impl Soup {
+    /// Returns a 16x16 asymmetric soup at 50% density.
+    pub fn new(seed: u64) -> Self {
+        Soup {
+            seed,
+            density: 0.5,
+            width: DEFAULT_REGION,
+            height: DEFAULT_REGION,
+            symmetry: Symmetry::default(),
+        }
+    }
+
+    /// Returns a soup like `Soup::new` with a seed picked at random.
+    pub fn random() -> Self {
+        Soup::new(thread_rng().gen())
+    }
+
+    /// Returns a soup with the same settings and a new random seed.
+    pub fn reroll(&self) -> Self {
+        self.with_seed(thread_rng().gen())
+    }
+
+    /// Replaces the seed, keeping every other setting.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Replaces the chance that each cell starts alive, from 0 to 1.
+    pub fn with_density(mut self, density: f64) -> Self {
+        self.density = density;
+        self
+    }
+
+    /// Replaces the size of the rectangle the soup fills.
+    pub fn with_region(mut self, width: usize, height: usize) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    pub fn with_symmetry(mut self, symmetry: Symmetry) -> Self {
+        self.symmetry = symmetry;
+        self
+    }
+
+    #[inline]
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    #[inline]
+    pub fn density(&self) -> f64 {
+        self.density
+    }
+
+    /// Width and height of the rectangle the soup fills
+    #[inline]
+    pub fn region(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    #[inline]
+    pub fn symmetry(&self) -> Symmetry {
+        self.symmetry
+    }
+
+    /// Generates the soup's cells.
+    /// Errors if the density isn't between 0 and 1, or if a D8 soup's
+    /// region isn't square.
+    pub fn generate(&self) -> anyhow::Result<BitBoard> {
+        if self.symmetry == Symmetry::D8 && self.width != self.height {
+            bail!(
+                "D8 soups need a square region, not {}x{}",
+                self.width,
+                self.height
+            );
+        }
+        let dist = Bernoulli::new(self.density)?;
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut cells = BitBoard::new(self.width, self.height);
+        for row in 0..self.height {
+            for col in 0..self.width {
+                let (rep_row, rep_col) =
+                    self.symmetry
+                        .representative(row, col, self.width, self.height);
+                let is_alive = if (rep_row, rep_col) == (row, col) {
+                    dist.sample(&mut rng)
+                } else {
+                    cells.get(rep_row, rep_col)
+                };
+                cells.set(row, col, is_alive);
+            }
+        }
+        Ok(cells)
+    }
+}
+
+impl fmt::Display for Soup {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "seed {}, {}x{} {}, density {}",
+            self.seed, self.width, self.height, self.symmetry, self.density
+        )
+    }
+}
+
+#[cfg(test)]
+mod soup_tests {
+    use super::{Soup, Symmetry};
+
+    #[test]
+    fn same_seed_same_cells() -> anyhow::Result<()> {
+        let soup = Soup::new(1234).with_region(40, 24);
+        let cells = soup.generate()?;
+        assert_eq!(soup.generate()?, cells);
+        assert_ne!(Soup::new(1235).with_region(40, 24).generate()?, cells);
+        assert!(soup.with_density(1.5).generate().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn density_extremes() -> anyhow::Result<()> {
+        let soup = Soup::new(7).with_region(9, 5);
+        assert_eq!(soup.with_density(0.).generate()?.population(), 0);
+        assert_eq!(soup.with_density(1.).generate()?.population(), 45);
+        let half = soup.with_region(100, 100).generate()?.population();
+        assert!((4500..5500).contains(&half), "{half}");
+        Ok(())
+    }
+
+    #[test]
+    fn symmetries() -> anyhow::Result<()> {
+        for (width, height) in [(16, 16), (15, 15), (13, 8)] {
+            let mirrored = |symmetry| {
+                Soup::new(99)
+                    .with_region(width, height)
+                    .with_symmetry(symmetry)
+                    .generate()
+            };
+            let d2 = mirrored(Symmetry::D2)?;
+            let d4 = mirrored(Symmetry::D4)?;
+            for row in 0..height {
+                for col in 0..width {
+                    let flip_col = width - 1 - col;
+                    let flip_row = height - 1 - row;
+                    assert_eq!(d2.get(row, col), d2.get(row, flip_col));
+                    assert_eq!(d4.get(row, col), d4.get(row, flip_col));
+                    assert_eq!(d4.get(row, col), d4.get(flip_row, col));
+                }
+            }
+            assert!(d2.population() > 0);
+
+            if width != height {
+                assert!(mirrored(Symmetry::D8).is_err());
+                continue;
+            }
+            let d8 = mirrored(Symmetry::D8)?;
+            for row in 0..height {
+                for col in 0..width {
+                    assert_eq!(d8.get(row, col), d8.get(col, row));
+                    assert_eq!(d8.get(row, col), d8.get(row, width - 1 - col));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn parse_and_display() -> anyhow::Result<()> {
+        assert_eq!("d4".parse::<Symmetry>()?, Symmetry::D4);
+        assert!("C3".parse::<Symmetry>().is_err());
+        let soup = Soup::new(42).with_symmetry(Symmetry::D8);
+        assert_eq!(soup.to_string(), "seed 42, 16x16 D8, density 0.5");
+        Ok(())
+    }
+}
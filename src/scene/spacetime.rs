@@ -0,0 +1,152 @@
+use super::{bitboard::BitBoard, mesh::cube_mesh, sim::ConwayGol, ChunkGrid};
+use bevy::prelude::*;
+use std::collections::VecDeque;
+
+/// Past generations shown by default
+pub const DEFAULT_SPACETIME_DEPTH: usize = 64;
+
+/// A view of the board's recent past, with each earlier generation stacked
+/// one layer further below the live board. Gliders trace diagonal tubes
+/// down through the stack, and oscillators trace columns.
+#[derive(Component, Debug)]
+pub struct Spacetime {
+    enabled: bool,
+    depth: usize,
+    /// Layer entities, newest first
+    layers: VecDeque<Entity>,
+    /// The generation last seen and its board
+    last: Option<(u64, BitBoard)>,
+    material: Option<Handle<StandardMaterial>>,
+}
+
+impl Default for Spacetime {
+    fn default() -> Self {
+        Spacetime::new(DEFAULT_SPACETIME_DEPTH)
+    }
+}
+
+impl Spacetime {
+    /// Returns a hidden view that keeps up to `depth` past generations once
+    /// shown.
+    pub fn new(depth: usize) -> Self {
+        Spacetime {
+            enabled: false,
+            depth,
+            layers: VecDeque::new(),
+            last: None,
+            material: None,
+        }
+    }
+
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+}
+
+/// Marker struct for the mesh showing one past generation
+#[derive(Component)]
+pub struct SpacetimeLayer;
+
+/// Toggles the spacetime view, and while it's shown, adds a layer for each
+/// generation the board leaves behind. Stepping back removes the newest
+/// layers, and any other jump in generations, like a reseed, starts the
+/// stack over.
+#[allow(clippy::type_complexity)]
+pub fn stack_generations(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut games: Query<(Entity, Ref<ConwayGol>, &ChunkGrid, &mut Spacetime)>,
+    mut layers: Query<&mut Transform, With<SpacetimeLayer>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    // Ctrl+V pastes a selection instead
+    let toggled = keys.just_pressed(KeyCode::KeyV)
+        && !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    for (game, gol, grid, mut spacetime) in &mut games {
+        if toggled {
+            spacetime.enabled = !spacetime.enabled;
+            if !spacetime.enabled {
+                for layer in spacetime.layers.drain(..) {
+                    commands.entity(layer).despawn_recursive();
+                }
+                spacetime.last = None;
+            }
+        }
+        if !spacetime.enabled || !(toggled || gol.is_changed()) {
+            continue;
+        }
+
+        let generation = gol.generation();
+        let board = gol.board().clone();
+        match spacetime.last.take() {
+            Some((last_gen, last_board)) if generation == last_gen + 1 => {
+                let material = spacetime
+                    .material
+                    .get_or_insert_with(|| {
+                        materials.add(StandardMaterial {
+                            base_color: Color::rgb(0.55, 0.65, 0.8),
+                            ..default()
+                        })
+                    })
+                    .clone();
+                let layer = commands
+                    .spawn((
+                        PbrBundle {
+                            mesh: meshes.add(cube_mesh(
+                                &last_board,
+                                grid.view(),
+                                grid.view(),
+                                grid.spacing(),
+                                |_, _| Color::WHITE,
+                            )),
+                            material,
+                            transform: Transform::from_translation(
+                                grid.cell_translation(grid.top as i64, grid.left as i64)
+                                    - grid.spacing() * Vec3::Y,
+                            ),
+                            ..default()
+                        },
+                        SpacetimeLayer,
+                    ))
+                    .id();
+                commands.entity(game).add_child(layer);
+                spacetime.layers.push_front(layer);
+            }
+            Some((last_gen, _)) if generation == last_gen => {}
+            Some((last_gen, _)) if generation < last_gen => {
+                for _ in generation..last_gen {
+                    if let Some(layer) = spacetime.layers.pop_front() {
+                        commands.entity(layer).despawn_recursive();
+                    }
+                }
+            }
+            _ => {
+                for layer in spacetime.layers.drain(..) {
+                    commands.entity(layer).despawn_recursive();
+                }
+            }
+        }
+        while spacetime.layers.len() > spacetime.depth {
+            if let Some(layer) = spacetime.layers.pop_back() {
+                commands.entity(layer).despawn_recursive();
+            }
+        }
+        spacetime.last = Some((generation, board));
+
+        // Layers spawned this frame don't exist until commands apply, but
+        // they start out in the newest slot anyway. Layers are spaced like
+        // the cubes within them.
+        for (ind, layer) in spacetime.layers.iter().enumerate() {
+            if let Ok(mut tform) = layers.get_mut(*layer) {
+                tform.translation.y = -grid.spacing() * (ind + 1) as f32;
+            }
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use super::{
+    bitboard::BitBoard,
+    rule::Rule,
+    sim::{Bounds, Universe},
+};
+use anyhow::bail;
+use bevy::ecs::component::Component;
+use std::collections::{HashMap, HashSet};
+
+/// An unbounded Game of Life universe that only stores its live cells, so
+/// patterns can travel forever without reaching an edge.
+#[derive(Debug, Default, Component)]
+pub struct SparseGol {
+    alive: HashSet<(i64, i64)>,
+    rule: Rule,
+}
+
+impl SparseGol {
+    /// Returns an empty universe following B3/S23.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Seeds a universe with the live cells of a board, placing the board's
+    /// top left cell at top, left.
+    pub fn from_board(board: &BitBoard, top: i64, left: i64) -> Self {
+        SparseGol {
+            alive: board
+                .iter_alive()
+                .map(|(row, col)| (top + row as i64, left + col as i64))
+                .collect(),
+            rule: Rule::default(),
+        }
+    }
+
+    /// Replaces the rule the universe evolves under, which is B3/S23 by default.
+    /// Errors if the rule has B0, which would fill the infinite plane in one tick.
+    pub fn with_rule(mut self, rule: Rule) -> anyhow::Result<Self> {
+        if rule.births(0) {
+            bail!("Rule {rule} has B0, which an unbounded universe can't simulate");
+        }
+        self.rule = rule;
+        Ok(self)
+    }
+
+    #[inline]
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    /// Iterates the row, col positions of every live cell in no particular order.
+    pub fn iter_alive(&self) -> impl Iterator<Item = (i64, i64)> + '_ {
+        self.alive.iter().copied()
+    }
+}
+
+impl Universe for SparseGol {
+    /// Only live cells and cells next to them can change, so neighbor counts
+    /// are accumulated by visiting each live cell's neighborhood. Live cells
+    /// start at zero so that lone ones survive under S0.
+    fn tick(&mut self) {
+        let mut nb_cts: HashMap<(i64, i64), usize> = HashMap::with_capacity(self.alive.len() * 4);
+        for &(row, col) in &self.alive {
+            nb_cts.entry((row, col)).or_default();
+            for d_row in -1..=1 {
+                for d_col in -1..=1 {
+                    if d_row != 0 || d_col != 0 {
+                        *nb_cts.entry((row + d_row, col + d_col)).or_default() += 1;
+                    }
+                }
+            }
+        }
+
+        let next = nb_cts
+            .into_iter()
+            .filter(|(cell, nb_ct)| self.rule.next_state(self.alive.contains(cell), *nb_ct))
+            .map(|(cell, _)| cell)
+            .collect();
+        self.alive = next;
+    }
+
+    fn is_alive(&self, row: i64, col: i64) -> bool {
+        self.alive.contains(&(row, col))
+    }
+
+    fn set(&mut self, row: i64, col: i64, is_alive: bool) {
+        if is_alive {
+            self.alive.insert((row, col));
+        } else {
+            self.alive.remove(&(row, col));
+        }
+    }
+
+    fn population(&self) -> usize {
+        self.alive.len()
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        Bounds::enclosing(self.iter_alive())
+    }
+
+    fn window(&self, top: i64, left: i64, width: usize, height: usize) -> BitBoard {
+        let mut board = BitBoard::new(width, height);
+        for &(row, col) in &self.alive {
+            let (row, col) = (row - top, col - left);
+            if (0..height as i64).contains(&row) && (0..width as i64).contains(&col) {
+                board.set(row as usize, col as usize, true);
+            }
+        }
+        board
+    }
+}
+
+#[cfg(test)]
+mod sparse_tests {
+    use super::SparseGol;
+    use crate::scene::{
+        bitboard::BitBoard,
+        sim::{Bounds, ConwayGol, Universe},
+    };
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    fn glider() -> SparseGol {
+        let mut gol = SparseGol::new();
+        for (row, col) in [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)] {
+            gol.set(row, col, true);
+        }
+        gol
+    }
+
+    /// A glider keeps going far past where any finite board would end
+    #[test]
+    fn glider_travels_forever() {
+        let mut gol = glider();
+        let start = gol.window(0, 0, 3, 3);
+        for _ in 0..4000 {
+            gol.tick();
+        }
+        assert_eq!(gol.population(), 5);
+        assert_eq!(
+            gol.bounds(),
+            Some(Bounds {
+                top: 1000,
+                left: 1000,
+                bottom: 1002,
+                right: 1002
+            })
+        );
+        assert_eq!(gol.window(1000, 1000, 3, 3), start);
+    }
+
+    /// Agrees with the dense backend while the pattern stays away from the edges
+    #[test]
+    fn matches_dense_backend() -> anyhow::Result<()> {
+        let mut rng = StdRng::seed_from_u64(4);
+        let mut board = BitBoard::new(96, 96);
+        for row in 40..56 {
+            for col in 40..56 {
+                board.set(row, col, rng.gen_bool(0.5));
+            }
+        }
+        let mut dense = ConwayGol::from_board(board.clone()).with_rule("B36/S23".parse()?);
+        let mut sparse = SparseGol::from_board(&board, -48, -48).with_rule("B36/S23".parse()?)?;
+        for _ in 0..20 {
+            dense.tick();
+            sparse.tick();
+            assert_eq!(dense.board(), &sparse.window(-48, -48, 96, 96));
+        }
+        Ok(())
+    }
+
+    /// Lone cells survive rules with S0, as they do on the dense backend
+    #[test]
+    fn matches_dense_backend_under_s0() -> anyhow::Result<()> {
+        let mut rng = StdRng::seed_from_u64(9);
+        let mut board = BitBoard::new(64, 64);
+        for row in 24..40 {
+            for col in 24..40 {
+                board.set(row, col, rng.gen_bool(0.2));
+            }
+        }
+        board.set(8, 8, true);
+        let rule = "B3/S023".parse()?;
+        let mut dense = ConwayGol::from_board(board.clone()).with_rule(rule);
+        let mut sparse = SparseGol::from_board(&board, 0, 0).with_rule(rule)?;
+        for _ in 0..10 {
+            dense.tick();
+            sparse.tick();
+            assert!(sparse.is_alive(8, 8));
+            assert_eq!(dense.board(), &sparse.window(0, 0, 64, 64));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_b0() {
+        assert!(SparseGol::new()
+            .with_rule("B0/S8".parse().unwrap())
+            .is_err());
+    }
+}
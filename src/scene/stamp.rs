@@ -0,0 +1,309 @@
+use super::{
+    bitboard::BitBoard,
+    mesh::{cube_mesh, empty_mesh},
+    pattern::library::{LibraryEntry, LIBRARY},
+    sim::{Bounds, ConwayGol},
+    ChunkGrid, CubeInd, Editor,
+};
+use bevy::prelude::*;
+
+/// A pattern picked from the built-in library or pasted from a selection to
+/// stamp onto the board, turned and flipped the way the player set it
+#[derive(Component, Debug, Default)]
+pub struct Stamp {
+    open: bool,
+    /// Index of the picked pattern in the library
+    selected: usize,
+    /// Cells pasted from a selection, stamped instead of the library's
+    /// pattern until another is picked
+    pasted: Option<BitBoard>,
+    /// Quarter turns clockwise
+    turns: u8,
+    flipped: bool,
+    /// The picked pattern's cells once turned and flipped
+    cells: Option<BitBoard>,
+}
+
+impl Stamp {
+    /// Whether the picker is open, in which case clicks stamp the picked
+    /// pattern instead of painting cells.
+    #[inline]
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    #[inline]
+    pub fn selected(&self) -> &'static LibraryEntry {
+        &LIBRARY[self.selected]
+    }
+
+    /// The cells that will be stamped, once a pattern's been picked.
+    #[inline]
+    pub fn cells(&self) -> Option<&BitBoard> {
+        self.cells.as_ref()
+    }
+
+    /// Opens the picker with pasted cells in place of the library's pattern,
+    /// upright and unflipped.
+    pub fn paste(&mut self, cells: BitBoard) {
+        self.open = true;
+        self.pasted = Some(cells);
+        self.turns = 0;
+        self.flipped = false;
+    }
+
+    /// The top left cell of the stamp when it's centered on a cell.
+    pub fn origin(&self, center: CubeInd) -> Option<(i64, i64)> {
+        let cells = self.cells.as_ref()?;
+        Some((
+            center.row as i64 - (cells.height() / 2) as i64,
+            center.col as i64 - (cells.width() / 2) as i64,
+        ))
+    }
+
+    /// Loads the picked or pasted pattern and applies its flip and turns.
+    fn orient(&mut self) -> anyhow::Result<()> {
+        let mut cells = match &self.pasted {
+            Some(pasted) => pasted.clone(),
+            None => self.selected().pattern()?.cells,
+        };
+        if self.flipped {
+            cells = cells.flipped();
+        }
+        for _ in 0..self.turns {
+            cells = cells.rotated();
+        }
+        self.cells = Some(cells);
+        Ok(())
+    }
+}
+
+/// Marker struct for the list of library patterns
+#[derive(Component)]
+pub struct PatternPicker;
+
+/// Marker struct for the see-through preview of the stamp
+#[derive(Component)]
+pub struct Ghost;
+
+/// Spawns the pattern picker and the stamp's preview, both hidden until the
+/// picker is opened.
+pub fn setup_stamp(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        TextBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font_size: 18.,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ),
+            visibility: Visibility::Hidden,
+            ..default()
+        }
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(18.),
+            right: Val::Px(18.),
+            ..default()
+        }),
+        PatternPicker,
+    ));
+
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(empty_mesh()),
+            material: materials.add(StandardMaterial {
+                base_color: Color::rgba(0.6, 0.9, 1., 0.35),
+                alpha_mode: AlphaMode::Blend,
+                unlit: true,
+                ..default()
+            }),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        Ghost,
+    ));
+}
+
+/// Opens and closes the pattern picker, which also turns on edit mode to aim
+/// the stamp. While it's open, picks patterns from the library and turns and
+/// flips them, keeping the list and the preview's shape up to date. Pastes
+/// open the picker too.
+pub fn pick_pattern(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut games: Query<(&mut Stamp, &mut Editor, &ChunkGrid)>,
+    mut picker: Query<(&mut Text, &mut Visibility), With<PatternPicker>>,
+    ghost: Query<&Handle<Mesh>, With<Ghost>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    let Ok((mut stamp, mut editor, grid)) = games.get_single_mut() else {
+        return;
+    };
+    // Changes from other systems are pastes
+    let mut changed = stamp.is_changed();
+    if keys.just_pressed(KeyCode::KeyP) {
+        stamp.open = !stamp.open;
+        changed = true;
+    }
+    if stamp.open {
+        if keys.just_pressed(KeyCode::BracketRight) {
+            stamp.selected = (stamp.selected + 1) % LIBRARY.len();
+            stamp.pasted = None;
+            changed = true;
+        }
+        if keys.just_pressed(KeyCode::BracketLeft) {
+            stamp.selected = (stamp.selected + LIBRARY.len() - 1) % LIBRARY.len();
+            stamp.pasted = None;
+            changed = true;
+        }
+        if keys.just_pressed(KeyCode::KeyT) {
+            stamp.turns = (stamp.turns + 1) % 4;
+            changed = true;
+        }
+        if keys.just_pressed(KeyCode::KeyF) {
+            stamp.flipped = !stamp.flipped;
+            changed = true;
+        }
+    }
+    if !changed {
+        return;
+    }
+
+    let vis = if stamp.open {
+        Visibility::Visible
+    } else {
+        Visibility::Hidden
+    };
+    for (_, mut picker_vis) in &mut picker {
+        picker_vis.set_if_neq(vis);
+    }
+    if !stamp.open {
+        return;
+    }
+    // Opening the pattern picker turns edit mode on too
+    editor.enabled = true;
+
+    stamp
+        .orient()
+        .expect("Built-in patterns are tested to parse");
+    for (mut text, _) in &mut picker {
+        text.sections[0].value = picker_text(&stamp);
+    }
+    let Some(cells) = stamp.cells() else {
+        return;
+    };
+    let whole = Bounds {
+        top: 0,
+        left: 0,
+        bottom: cells.height() as i64 - 1,
+        right: cells.width() as i64 - 1,
+    };
+    for handle in &ghost {
+        if let Some(mesh) = meshes.get_mut(handle) {
+            *mesh = cube_mesh(cells, whole, whole, grid.spacing(), |_, _| Color::WHITE);
+        }
+    }
+}
+
+/// Lists the library with the picked pattern marked, followed by its
+/// keys.
+fn picker_text(stamp: &Stamp) -> String {
+    let mut text = String::from("patterns ([ and ] to pick)\n");
+    if let Some(pasted) = &stamp.pasted {
+        text.push_str(&format!(
+            "> pasted cells ({}x{})\n",
+            pasted.width(),
+            pasted.height()
+        ));
+    }
+    for (ind, entry) in LIBRARY.iter().enumerate() {
+        let mark = if ind == stamp.selected && stamp.pasted.is_none() {
+            ">"
+        } else {
+            " "
+        };
+        text.push_str(&format!("{mark} {} ({})\n", entry.name, entry.category));
+    }
+    text.push_str(&format!(
+        "\nt: turn ({} degrees)\nf: flip ({})\nclick: stamp\np: close",
+        stamp.turns as u32 * 90,
+        if stamp.flipped { "on" } else { "off" }
+    ));
+    text
+}
+
+/// While the picker is open, shows the preview centered on the cell under
+/// the crosshair and stamps the pattern there on a left click. The stamp
+/// overwrites the cells under it, and whatever hangs off the board is
+/// dropped.
+pub fn stamp_pattern(
+    buttons: Res<ButtonInput<MouseButton>>,
+    mut games: Query<(
+        &mut ConwayGol,
+        &Stamp,
+        &Editor,
+        &ChunkGrid,
+        &GlobalTransform,
+    )>,
+    mut ghost: Query<(&mut Transform, &mut Visibility), With<Ghost>>,
+) {
+    let Ok((mut gol, stamp, editor, grid, grid_tform)) = games.get_single_mut() else {
+        return;
+    };
+    let origin = editor
+        .target
+        .filter(|_| stamp.is_open() && editor.is_enabled())
+        .and_then(|target| stamp.origin(target));
+    for (mut tform, mut vis) in &mut ghost {
+        let Some((top, left)) = origin else {
+            vis.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+        tform.translation = grid_tform.transform_point(grid.cell_translation(top, left));
+        vis.set_if_neq(Visibility::Visible);
+    }
+
+    if let (Some((top, left)), Some(cells)) = (origin, stamp.cells()) {
+        if buttons.just_pressed(MouseButton::Left) {
+            gol.stamp(cells, top, left);
+        }
+    }
+}
+
+#[cfg(test)]
+mod stamp_tests {
+    use super::Stamp;
+    use crate::scene::{bitboard::BitBoard, pattern::library::LIBRARY, CubeInd};
+
+    #[test]
+    fn orients_around_the_center() -> anyhow::Result<()> {
+        let glider = LIBRARY
+            .iter()
+            .position(|entry| entry.name == "Glider")
+            .expect("The library has a glider");
+        let mut stamp = Stamp {
+            selected: glider,
+            ..Stamp::default()
+        };
+        let center = CubeInd { row: 5, col: 5 };
+        assert_eq!(stamp.origin(center), None);
+
+        stamp.turns = 1;
+        stamp.flipped = true;
+        stamp.orient()?;
+        let expected = BitBoard::from_rows(&[
+            vec![true, true, false],
+            vec![true, false, true],
+            vec![true, false, false],
+        ])?;
+        assert_eq!(stamp.cells(), Some(&expected));
+        assert_eq!(stamp.origin(center), Some((4, 4)));
+        Ok(())
+    }
+}
@@ -0,0 +1,145 @@
+use anyhow::bail;
+use std::{fmt, str::FromStr};
+
+/// How the edges of a finite board are glued together, which decides what
+/// a cell on the border sees when it looks past the edge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Topology {
+    /// Everything past the edge is permanently dead
+    #[default]
+    Bounded,
+    /// Everything past the edge is permanently alive
+    AliveEdge,
+    /// Left joins right and top joins bottom
+    Torus,
+    /// Left joins right, and top joins bottom with a horizontal flip
+    KleinBottle,
+    /// Left joins right with a vertical flip, and top joins bottom with a
+    /// horizontal flip. The corners are singular points, so neighbors reached
+    /// diagonally across a corner read as dead.
+    CrossSurface,
+}
+
+/// What a position one step off the board refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Neighbor {
+    Cell { row: usize, col: usize },
+    Dead,
+    Alive,
+}
+
+impl Topology {
+    /// Whether cells past one edge are read from cells along another edge.
+    #[inline]
+    pub fn wraps(&self) -> bool {
+        !matches!(self, Topology::Bounded | Topology::AliveEdge)
+    }
+
+    /// Maps a row, col that lies at most one step outside a width x height board
+    /// to the cell it refers to. On-board positions map to themselves.
+    pub fn resolve(&self, row: isize, col: isize, width: usize, height: usize) -> Neighbor {
+        let (width, height) = (width as isize, height as isize);
+        let row_off = row < 0 || row >= height;
+        let col_off = col < 0 || col >= width;
+        if !row_off && !col_off {
+            return Neighbor::Cell {
+                row: row as usize,
+                col: col as usize,
+            };
+        }
+
+        let (row, col) = match self {
+            Topology::Bounded => return Neighbor::Dead,
+            Topology::AliveEdge => return Neighbor::Alive,
+            Topology::Torus => (row.rem_euclid(height), col.rem_euclid(width)),
+            Topology::KleinBottle => {
+                if row_off {
+                    (row.rem_euclid(height), (width - 1 - col).rem_euclid(width))
+                } else {
+                    (row, col.rem_euclid(width))
+                }
+            }
+            Topology::CrossSurface => match (row_off, col_off) {
+                (true, true) => return Neighbor::Dead,
+                (true, false) => (row.rem_euclid(height), width - 1 - col),
+                (false, _) => (height - 1 - row, col.rem_euclid(width)),
+            },
+        };
+        Neighbor::Cell {
+            row: row as usize,
+            col: col as usize,
+        }
+    }
+}
+
+impl FromStr for Topology {
+    type Err = anyhow::Error;
+
+    /// Parses the names `Topology` displays as, case insensitive.
+    fn from_str(name: &str) -> anyhow::Result<Self> {
+        let topology = match name.trim().to_ascii_lowercase().as_str() {
+            "bounded" => Topology::Bounded,
+            "alive-edge" => Topology::AliveEdge,
+            "torus" => Topology::Torus,
+            "klein-bottle" => Topology::KleinBottle,
+            "cross-surface" => Topology::CrossSurface,
+            _ => bail!("Unknown topology {name:?}"),
+        };
+        Ok(topology)
+    }
+}
+
+impl fmt::Display for Topology {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Topology::Bounded => "bounded",
+            Topology::AliveEdge => "alive-edge",
+            Topology::Torus => "torus",
+            Topology::KleinBottle => "klein-bottle",
+            Topology::CrossSurface => "cross-surface",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[cfg(test)]
+mod topology_tests {
+    use super::{Neighbor, Topology};
+
+    #[test]
+    fn resolve_edges() {
+        let cell = |row, col| Neighbor::Cell { row, col };
+        let (w, h) = (5, 4);
+
+        assert_eq!(Topology::Bounded.resolve(2, 3, w, h), cell(2, 3));
+        assert_eq!(Topology::Bounded.resolve(-1, 3, w, h), Neighbor::Dead);
+        assert_eq!(Topology::AliveEdge.resolve(2, 5, w, h), Neighbor::Alive);
+
+        assert_eq!(Topology::Torus.resolve(-1, -1, w, h), cell(3, 4));
+        assert_eq!(Topology::Torus.resolve(4, 5, w, h), cell(0, 0));
+
+        assert_eq!(Topology::KleinBottle.resolve(-1, 1, w, h), cell(3, 3));
+        assert_eq!(Topology::KleinBottle.resolve(1, 5, w, h), cell(1, 0));
+        assert_eq!(Topology::KleinBottle.resolve(4, -1, w, h), cell(0, 0));
+
+        assert_eq!(Topology::CrossSurface.resolve(4, 0, w, h), cell(0, 4));
+        assert_eq!(Topology::CrossSurface.resolve(0, -1, w, h), cell(3, 4));
+        assert_eq!(Topology::CrossSurface.resolve(-1, -1, w, h), Neighbor::Dead);
+    }
+
+    #[test]
+    fn names_round_trip() -> anyhow::Result<()> {
+        for topology in [
+            Topology::Bounded,
+            Topology::AliveEdge,
+            Topology::Torus,
+            Topology::KleinBottle,
+            Topology::CrossSurface,
+        ] {
+            assert_eq!(topology.to_string().parse::<Topology>()?, topology);
+        }
+        assert_eq!(" Torus ".parse::<Topology>()?, Topology::Torus);
+        assert!("sphere".parse::<Topology>().is_err());
+        Ok(())
+    }
+}
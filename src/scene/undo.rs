@@ -0,0 +1,155 @@
+use super::sim::{ConwayGol, EditCommand};
+use bevy::prelude::*;
+
+/// Edits remembered by default
+pub const DEFAULT_UNDO_DEPTH: usize = 256;
+
+/// The player's edits to the board, which can be undone and redone
+/// separately from stepping back through generations
+#[derive(Component, Debug)]
+pub struct UndoStack {
+    /// Oldest first
+    undo: Vec<EditCommand>,
+    /// Most recently undone last
+    redo: Vec<EditCommand>,
+    depth: usize,
+}
+
+impl Default for UndoStack {
+    fn default() -> Self {
+        UndoStack::new(DEFAULT_UNDO_DEPTH)
+    }
+}
+
+impl UndoStack {
+    /// Returns an empty stack that remembers at most `depth` edits.
+    pub fn new(depth: usize) -> Self {
+        UndoStack {
+            undo: Vec::new(),
+            redo: Vec::new(),
+            depth,
+        }
+    }
+
+    /// Number of edits that can currently be undone.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.undo.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.undo.is_empty()
+    }
+
+    /// Forgets every edit, as when the board is replaced wholesale.
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    /// Remembers a new edit, forgetting the oldest once the stack is full
+    /// and every edit that was undone.
+    pub fn push(&mut self, command: EditCommand) {
+        if self.depth == 0 {
+            return;
+        }
+        if self.undo.len() == self.depth {
+            self.undo.remove(0);
+        }
+        self.undo.push(command);
+        self.redo.clear();
+    }
+
+    /// Reverts the latest edit. Returns false if there's nothing to undo.
+    pub fn undo(&mut self, gol: &mut ConwayGol) -> bool {
+        let Some(command) = self.undo.pop() else {
+            return false;
+        };
+        gol.undo_edit(&command);
+        self.redo.push(command);
+        true
+    }
+
+    /// Reapplies the latest undone edit. Returns false if there's nothing
+    /// to redo.
+    pub fn redo(&mut self, gol: &mut ConwayGol) -> bool {
+        let Some(command) = self.redo.pop() else {
+            return false;
+        };
+        gol.redo_edit(&command);
+        self.undo.push(command);
+        true
+    }
+}
+
+/// Collects the edits made to the board into the undo stack, and undoes
+/// and redoes them on ctrl+z and ctrl+shift+z. Edits made while a mouse
+/// button is held, like a paint stroke, are kept together as one.
+pub fn track_edits(
+    keys: Res<ButtonInput<KeyCode>>,
+    buttons: Res<ButtonInput<MouseButton>>,
+    mut games: Query<(&mut ConwayGol, &mut UndoStack)>,
+) {
+    let Ok((mut gol, mut stack)) = games.get_single_mut() else {
+        return;
+    };
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let undo = ctrl && keys.just_pressed(KeyCode::KeyZ);
+    let stroke_done = !buttons.any_pressed([MouseButton::Left, MouseButton::Right]);
+
+    // Recording doesn't change the board, so it's left out of change
+    // detection to save the renderer a look
+    let recorder = gol.bypass_change_detection();
+    if stroke_done || undo {
+        if let Some(command) = recorder.end_edit() {
+            stack.push(command);
+        }
+    }
+    recorder.begin_edit();
+
+    if undo {
+        if shift {
+            stack.redo(&mut gol);
+        } else {
+            stack.undo(&mut gol);
+        }
+    }
+}
+
+#[cfg(test)]
+mod undo_tests {
+    use super::UndoStack;
+    use crate::scene::{
+        bitboard::BitBoard,
+        sim::{ConwayGol, Universe},
+    };
+
+    #[test]
+    fn undo_then_redo() {
+        let mut gol = ConwayGol::from_board(BitBoard::new(8, 8));
+        let mut stack = UndoStack::new(2);
+        for col in 0..3 {
+            gol.begin_edit();
+            gol.set(1, col, true);
+            stack.push(gol.end_edit().expect("Setting a dead cell flips it"));
+        }
+        assert_eq!(stack.len(), 2);
+
+        // The oldest edit was forgotten
+        assert!(stack.undo(&mut gol) && stack.undo(&mut gol));
+        assert!(!stack.undo(&mut gol));
+        assert_eq!(gol.board().iter_alive().collect::<Vec<_>>(), vec![(1, 0)]);
+
+        assert!(stack.redo(&mut gol));
+        assert_eq!(gol.board().population(), 2);
+        // A new edit drops what's left to redo
+        gol.begin_edit();
+        gol.set(5, 5, true);
+        stack.push(gol.end_edit().expect("Setting a dead cell flips it"));
+        assert!(!stack.redo(&mut gol));
+        assert!(stack.undo(&mut gol));
+        assert_eq!(gol.board().population(), 2);
+    }
+}
@@ -1,4 +1,16 @@
-use super::{sim::ConwayGol, CameraRotation, CubeInd, GameTimer, Paused, BOARD_SIZE, CUBE_SPACING};
+use super::{
+    bitboard::BitBoard,
+    config::GameConfig,
+    gradient::AgeGradient,
+    mesh::{cube_mesh, empty_mesh},
+    select::Selection,
+    sim::{Bounds, ConwayGol, CycleDetector, Universe},
+    soup::Soup,
+    spacetime::Spacetime,
+    stamp::Stamp,
+    undo::UndoStack,
+    BoardSettled, CameraRotation, ChunkGrid, Editor, GameTimer, Paused, SettleAction,
+};
 use bevy::prelude::*;
 
 /// Creates one-time world assets like the camera, sky, and sun.
@@ -6,14 +18,17 @@ pub fn setup_world(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
+    config: Res<GameConfig>,
 ) {
-    let offset = BOARD_SIZE as f32;
+    let (view_width, view_height) = config.view();
+    let offset = view_width.max(view_height) as f32;
+    let (translation, rotation) = camera_start(view_width, view_height, config.spacing());
     commands.spawn((
         Camera3dBundle {
-            transform: Transform::from_xyz(8., 8., 0.).looking_at(Vec3::ZERO, Vec3::Y),
+            transform: Transform::from_translation(translation),
             ..Camera3dBundle::default()
         },
-        CameraRotation::default(),
+        rotation,
     ));
 
     // A dome around the world to reflect back the sun
@@ -42,77 +57,318 @@ pub fn setup_world(
     });
 }
 
+/// Places the camera just past the middle of one of the grid's short edges,
+/// looking down its long axis so that strips are seen end to end.
+fn camera_start(view_width: usize, view_height: usize, spacing: f32) -> (Vec3, CameraRotation) {
+    let height = 8.;
+    if view_width >= view_height {
+        // With no yaw the camera looks toward -z, from column 0 to the last one
+        let z = spacing * (view_width as f32 / 2. + 4.);
+        (Vec3::new(0., height, z), CameraRotation::default())
+    } else {
+        // A quarter turn looks toward -x, from row 0 to the last one
+        let x = spacing * (view_height as f32 / 2. + 4.);
+        let rotation = CameraRotation {
+            yaw: 90.,
+            pitch: 0.,
+        };
+        (Vec3::new(x, height, 0.), rotation)
+    }
+}
+
 /// Builds the Game of Life simulation and sets up the geometries used
-/// to render it.
+/// to render it, with live cells colored by age.
 pub fn init_conway_grid(
+    commands: Commands,
+    meshes: ResMut<Assets<Mesh>>,
+    materials: ResMut<Assets<StandardMaterial>>,
+    config: Res<GameConfig>,
+) {
+    let (gol, soup) = config
+        .build()
+        .expect("Conway grid must initialize in order to continue");
+    let detection = (CycleDetector::default(), SettleAction::default());
+    let views = (
+        config.gradient().clone(),
+        Spacetime::new(config.spacetime_depth()),
+        Editor::default(),
+        Stamp::default(),
+        Selection::default(),
+        UndoStack::default(),
+    );
+    // Boards started from a pattern have no soup to reseed from
+    match soup {
+        Some(soup) => spawn_game(
+            commands,
+            meshes,
+            materials,
+            &config,
+            (gol, soup, detection, views),
+        ),
+        None => spawn_game(
+            commands,
+            meshes,
+            materials,
+            &config,
+            (gol, detection, views),
+        ),
+    }
+}
+
+/// Builds an unbounded Game of Life simulation starting from the same soup
+/// or pattern as `init_conway_grid` would, along with a grid of cubes that
+/// follows the camera around the universe.
+pub fn init_sparse_grid(
+    commands: Commands,
+    meshes: ResMut<Assets<Mesh>>,
+    materials: ResMut<Assets<StandardMaterial>>,
+    config: Res<GameConfig>,
+) {
+    let (gol, soup) = config
+        .build_sparse()
+        .expect("Conway grid must initialize in order to continue");
+    match soup {
+        Some(soup) => spawn_game(commands, meshes, materials, &config, (gol, soup)),
+        None => spawn_game(commands, meshes, materials, &config, gol),
+    }
+}
+
+/// Builds a HashLife simulation starting from the same soup or pattern as
+/// `init_sparse_grid`, except that patterns can be any size, along with a
+/// grid of cubes that follows the camera around the universe.
+pub fn init_hashlife_grid(
+    commands: Commands,
+    meshes: ResMut<Assets<Mesh>>,
+    materials: ResMut<Assets<StandardMaterial>>,
+    config: Res<GameConfig>,
+) {
+    let (hl, soup) = config
+        .build_hashlife()
+        .expect("Conway grid must initialize in order to continue");
+    match soup {
+        Some(soup) => spawn_game(commands, meshes, materials, &config, (hl, soup)),
+        None => spawn_game(commands, meshes, materials, &config, hl),
+    }
+}
+
+/// Spawns the entity holding a simulation backend and its game state, with
+/// one child mesh per chunk of visible cells. The meshes start out empty and
+/// are filled in by the tick systems. The view, timer, and whether the game
+/// starts paused come from the config.
+fn spawn_game(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
+    config: &GameConfig,
+    sim: impl Bundle,
 ) {
-    // Oversize the board to make the edges look more alive
-    let gol = ConwayGol::build_rand(BOARD_SIZE * 2)
-        .expect("Conway grid must initialize in order to continue");
-
-    let cube_mesh = meshes.add(Cuboid::new(2., 2., 2.));
+    // Cubes are colored by their vertices
     let cube_mat = materials.add(StandardMaterial {
         base_color: Color::WHITE,
         ..default()
     });
 
-    commands
-        .spawn_empty()
-        .insert(gol)
-        .insert(Paused(true))
-        .insert(GameTimer::default())
+    let (view_width, view_height) = config.view();
+    let (row_offset, col_offset) = (view_height / 2, view_width / 2);
+    let mut grid = ChunkGrid::new(row_offset, col_offset, view_width, view_height)
+        .with_spacing(config.spacing());
+    let mut game = commands.spawn_empty();
+    game.insert(sim)
+        .insert(Paused(config.is_paused()))
+        .insert(GameTimer::new(config.tick()))
         .insert(Transform::default())
         .insert(GlobalTransform::default())
         .insert(InheritedVisibility::default())
         .with_children(|parent| {
-            let middle_cube = BOARD_SIZE as f32 / 2.;
-            let board_offset = BOARD_SIZE / 2;
-
-            let live_region = board_offset..(BOARD_SIZE + board_offset);
-            for row in live_region.clone() {
-                for col in live_region.clone() {
-                    let x = CUBE_SPACING * (middle_cube - (row - board_offset) as f32);
-                    let z = CUBE_SPACING * (middle_cube - (col - board_offset) as f32);
-                    parent.spawn((
-                        PbrBundle {
-                            // resource handles have cheap clone
-                            mesh: cube_mesh.clone(),
-                            material: cube_mat.clone(),
-                            transform: Transform::from_xyz(x, 0., z),
-                            ..Default::default()
-                        },
-                        CubeInd { row, col },
-                    ));
-                }
+            for ind in 0..grid.chunk_count() {
+                let bounds = grid.chunk_bounds(ind);
+                let chunk = parent.spawn(PbrBundle {
+                    mesh: meshes.add(empty_mesh()),
+                    // resource handles have cheap clone
+                    material: cube_mat.clone(),
+                    transform: Transform::from_translation(
+                        grid.cell_translation(bounds.top, bounds.left),
+                    ),
+                    ..Default::default()
+                });
+                grid.chunks.push(chunk.id());
             }
         });
+    game.insert(grid);
+}
+
+/// Rebuilds the meshes of the chunks marked dirty from a board whose top
+/// left cell is the grid's cell at `corner`. Colors are picked by board
+/// row, col.
+fn redraw_chunks(
+    grid: &ChunkGrid,
+    dirty: &[bool],
+    board: &BitBoard,
+    corner: (i64, i64),
+    color: impl Fn(usize, usize) -> Color,
+    chunks: &Query<&Handle<Mesh>>,
+    meshes: &mut Assets<Mesh>,
+) {
+    let on_board = |bounds: Bounds| Bounds {
+        top: bounds.top - corner.0,
+        left: bounds.left - corner.1,
+        bottom: bounds.bottom - corner.0,
+        right: bounds.right - corner.1,
+    };
+    for (ind, chunk) in grid.chunks().iter().enumerate() {
+        if !dirty[ind] {
+            continue;
+        }
+        let Some(mesh) = chunks
+            .get(*chunk)
+            .ok()
+            .and_then(|mesh| meshes.get_mut(mesh))
+        else {
+            continue;
+        };
+        *mesh = cube_mesh(
+            board,
+            on_board(grid.chunk_bounds(ind)),
+            on_board(grid.view()),
+            grid.spacing(),
+            &color,
+        );
+    }
 }
 
 /// Every time the timer completes, computes the next Game of Life board
-/// state and updates resources controlled by the simulation.
+/// state and updates resources controlled by the simulation. Only the chunks
+/// holding cells that flipped, whether by a tick or by stepping through
+/// history, are redrawn, along with chunks holding live cells young enough
+/// that their `AgeGradient` color is still changing. Once the board settles,
+/// sends a `BoardSettled` event and follows the game's `SettleAction`.
+#[allow(clippy::type_complexity)]
 pub fn next_game_tick(
-    mut game_state: Query<(&mut ConwayGol, &Paused, &mut GameTimer)>,
-    mut cubes: Query<(&mut Visibility, &CubeInd)>,
+    mut game_state: Query<(
+        &mut ConwayGol,
+        &mut Paused,
+        &mut GameTimer,
+        Ref<ChunkGrid>,
+        Option<(&mut CycleDetector, &SettleAction)>,
+        Option<&mut Soup>,
+        Option<Ref<AgeGradient>>,
+    )>,
+    chunks: Query<&Handle<Mesh>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut settled_events: EventWriter<BoardSettled>,
     time: Res<Time>,
 ) {
-    let (mut game_state, sim, mut timer) = game_state.single_mut();
-    if !timer.0.tick(time.delta()).finished() {
+    let Ok((mut game_state, mut sim, mut timer, grid, detection, soup, gradient)) =
+        game_state.get_single_mut()
+    else {
         return;
+    };
+    let ticked = timer.0.tick(time.delta()).finished() && !sim.is_paused();
+    if ticked {
+        game_state.tick();
     }
-    if sim.is_paused() {
-        return;
+
+    if let (true, Some((mut detector, action))) = (ticked, detection) {
+        if let Some(settled) = detector.observe(&game_state) {
+            settled_events.send(BoardSettled(settled));
+            match (action, soup) {
+                (SettleAction::Pause, _) => *sim = Paused(true),
+                (SettleAction::Reseed, Some(mut soup)) => {
+                    *soup = soup.reroll();
+                    // The player didn't make this reseed, so it isn't theirs to undo
+                    game_state
+                        .reseed_unrecorded(&soup)
+                        .expect("A soup that built the board must fit it again");
+                    detector.reset();
+                }
+                _ => {}
+            }
+        }
     }
 
-    game_state.tick();
-    let board = game_state.board();
-    for (mut vis, pos) in &mut cubes {
-        *vis = if board[pos.row][pos.col] {
-            Visibility::Visible
-        } else {
-            Visibility::Hidden
+    let redraw_all = grid.is_added() || gradient.as_ref().is_some_and(|g| g.is_changed());
+    let mut dirty = vec![redraw_all; grid.chunks().len()];
+    let gol: &ConwayGol = &game_state;
+    if !redraw_all && game_state.is_changed() {
+        let mut mark = |row: usize, col: usize| {
+            if let Some(ind) = grid.chunk_of(row, col) {
+                dirty[ind] = true;
+            }
         };
+        for (row, col) in gol.changed().iter_alive() {
+            // A flip also covers or uncovers a face of each neighbor's cube
+            mark(row, col);
+            mark(row.wrapping_sub(1), col);
+            mark(row + 1, col);
+            mark(row, col.wrapping_sub(1));
+            mark(row, col + 1);
+        }
+        // Cells keep aging without flipping until they reach the last color
+        if let Some(gradient) = &gradient {
+            for (row, col) in gol.board().iter_alive() {
+                if gol.age(row, col) <= gradient.max_age() {
+                    mark(row, col);
+                }
+            }
+        }
     }
+    let color = |row, col| {
+        gradient
+            .as_ref()
+            .map_or(Color::WHITE, |gradient| gradient.color(gol.age(row, col)))
+    };
+    redraw_chunks(
+        &grid,
+        &dirty,
+        gol.board(),
+        (0, 0),
+        color,
+        &chunks,
+        &mut meshes,
+    );
+}
+
+/// Ticks an unbounded simulation on the game timer and keeps the chunk grid
+/// showing the window of the universe centered under the camera.
+pub fn next_universe_tick<T: Universe + Component>(
+    mut game_state: Query<(&mut T, &Paused, &mut GameTimer, &mut Transform, &ChunkGrid)>,
+    camera: Query<&Transform, (With<CameraRotation>, Without<T>)>,
+    chunks: Query<&Handle<Mesh>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    time: Res<Time>,
+) {
+    let Ok((mut game_state, sim, mut timer, mut grid_tform, grid)) = game_state.get_single_mut()
+    else {
+        return;
+    };
+    let ticked = timer.0.tick(time.delta()).finished() && !sim.is_paused();
+    if ticked {
+        game_state.tick();
+    }
+
+    // Universe cell row, col sits at world x, z = -spacing * (row, col)
+    // plus the fixed offset of the grid layout, so shifting the grid by whole
+    // cells keeps every cube lined up with the cell it shows.
+    let cam = camera.single().translation;
+    let spacing = grid.spacing();
+    let top = (-cam.x / spacing).round() as i64;
+    let left = (-cam.z / spacing).round() as i64;
+    let grid_pos = Vec3::new(-spacing * top as f32, 0., -spacing * left as f32);
+    if !ticked && grid_tform.translation == grid_pos {
+        return;
+    }
+    grid_tform.translation = grid_pos;
+
+    // Only the cells in view are drawn, so only they are copied out
+    let view = grid.view();
+    let window = game_state.window(
+        top + view.top,
+        left + view.left,
+        view.width(),
+        view.height(),
+    );
+    let dirty = vec![true; grid.chunks().len()];
+    let color = |_, _| Color::WHITE;
+    let corner = (view.top, view.left);
+    redraw_chunks(grid, &dirty, &window, corner, color, &chunks, &mut meshes);
 }